@@ -0,0 +1,329 @@
+/*
+    ncursesw-derive/src/lib.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+//! Derive macros for the `ncursesw` crate, enabled in `ncursesw` with the `derive` feature.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    Data, DeriveInput, Expr, Fields, GenericArgument, Ident, LitChar, LitInt, LitStr,
+    PathArguments, Type, parse_macro_input, spanned::Spanned
+};
+
+// the width of a field when no width is given.
+const DEFAULT_WIDTH: i32 = 20;
+
+/// Implement `ncursesw::form::FormStruct` for a struct with named members.
+///
+/// Each member is edited in a field labelled with the member's name unless it
+/// is annotated with `#[form(...)]` which takes the following arguments:
+///
+/// - `label = "..."` the label of the field (and the name of the field).
+/// - `width = N` the width of the field, the default is 20 columns.
+/// - `lines = N` the height of a multi-line field, the default is 1 line.
+/// - `validator = expr` an expression returning the `FieldType` of the field.
+/// - `password` the field is a password field (see `set_field_password()`).
+/// - `mask = 'c'` the character displayed in place of each character of a password field.
+/// - `skip` the member is not edited and is set with `Default::default()`.
+///
+/// The type of each member must implement `FromStr` and `ToString`, an
+/// `Option<T>` member is `None` when its field is empty. A value that can not
+/// be parsed is reported with the label of its field and the reason in
+/// `NCurseswFormError::ParseFields`.
+///
+/// ```text
+/// #[derive(Form)]
+/// struct Config {
+///     #[form(label = "Host name", width = 30)]
+///     host: String,
+///     #[form(label = "Port", width = 5, validator = FieldType::port())]
+///     port: u16,
+///     #[form(label = "Timeout", width = 5)]
+///     timeout: Option<u32>
+/// }
+/// ```
+#[proc_macro_derive(Form, attributes(form))]
+pub fn derive_form(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand_form(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+// the arguments of a member's `#[form(...)]` attributes.
+struct FormMember {
+    ident:     Ident,
+    ty:        Type,
+    label:     String,
+    width:     i32,
+    lines:     i32,
+    validator: Option<Expr>,
+    password:  bool,
+    mask:      Option<char>,
+    skip:      bool
+}
+
+impl FormMember {
+    fn parse(field: &syn::Field) -> syn::Result<Self> {
+        let ident = field.ident.clone().ok_or_else(|| syn::Error::new(field.span(), "#[derive(Form)] requires named members"))?;
+
+        let mut member = Self {
+            label: ident.to_string(),
+            ident,
+            ty:        field.ty.clone(),
+            width:     DEFAULT_WIDTH,
+            lines:     1,
+            validator: None,
+            password:  false,
+            mask:      None,
+            skip:      false
+        };
+
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("form")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("label") {
+                    member.label = meta.value()?.parse::<LitStr>()?.value();
+                } else if meta.path.is_ident("width") {
+                    member.width = positive(meta.value()?.parse::<LitInt>()?)?;
+                } else if meta.path.is_ident("lines") {
+                    member.lines = positive(meta.value()?.parse::<LitInt>()?)?;
+                } else if meta.path.is_ident("validator") {
+                    member.validator = Some(meta.value()?.parse::<Expr>()?);
+                } else if meta.path.is_ident("password") {
+                    member.password = true;
+                } else if meta.path.is_ident("mask") {
+                    member.mask = Some(meta.value()?.parse::<LitChar>()?.value());
+                } else if meta.path.is_ident("skip") {
+                    member.skip = true;
+                } else {
+                    return Err(meta.error("unsupported form argument, expected one of `label`, `width`, `lines`, `validator`, `password`, `mask` or `skip`"));
+                }
+
+                Ok(())
+            })?;
+        }
+
+        if member.mask.is_some() && !member.password {
+            return Err(syn::Error::new_spanned(field, "`mask` requires `password`"));
+        }
+
+        if member.password && member.lines > 1 {
+            return Err(syn::Error::new_spanned(field, "a `password` field can not have more than one line"));
+        }
+
+        Ok(member)
+    }
+
+    // the `FieldSpec` of the member's field.
+    fn spec(&self) -> TokenStream2 {
+        let (width, lines) = (self.width, self.lines);
+
+        let spec = if self.password {
+            let mask = match self.mask {
+                Some(mask) => quote!(::std::option::Option::Some(#mask)),
+                None       => quote!(::std::option::Option::None)
+            };
+
+            quote!(::ncursesw::form::FieldSpec::password(#width, #mask))
+        } else if lines > 1 {
+            quote!(::ncursesw::form::FieldSpec::multi_line(#lines, #width))
+        } else {
+            quote!(::ncursesw::form::FieldSpec::text(#width))
+        };
+
+        match &self.validator {
+            Some(validator) => quote!(#spec.set_field_type(#validator)),
+            None            => spec
+        }
+    }
+
+    // the member is an `Option<T>`.
+    fn is_optional(&self) -> bool {
+        match &self.ty {
+            Type::Path(path) if path.qself.is_none() => path.path.segments.last().is_some_and(|segment| {
+                segment.ident == "Option" && matches!(&segment.arguments, PathArguments::AngleBracketed(args)
+                    if args.args.len() == 1 && matches!(args.args.first(), Some(GenericArgument::Type(_))))
+            }),
+            _                                        => false
+        }
+    }
+}
+
+fn positive(lit: LitInt) -> syn::Result<i32> {
+    match lit.base10_parse::<i32>()? {
+        n if n > 0 => Ok(n),
+        _          => Err(syn::Error::new(lit.span(), "expected a positive number"))
+    }
+}
+
+fn expand_form(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _                     => return Err(syn::Error::new(input.span(), "#[derive(Form)] requires a struct with named members"))
+        },
+        _                  => return Err(syn::Error::new(input.span(), "#[derive(Form)] requires a struct"))
+    };
+
+    let members = fields.iter().map(FormMember::parse).collect::<syn::Result<Vec<_>>>()?;
+    let (edited, skipped): (Vec<&FormMember>, Vec<&FormMember>) = members.iter().partition(|member| !member.skip);
+
+    if edited.is_empty() {
+        return Err(syn::Error::new(input.span(), "#[derive(Form)] requires at least one member that is not skipped"));
+    }
+
+    for (i, member) in edited.iter().enumerate() {
+        if edited[..i].iter().any(|other| other.label == member.label) {
+            return Err(syn::Error::new(member.ident.span(), format!("duplicate form label \"{}\"", member.label)));
+        }
+    }
+
+    let name = &input.ident;
+    let func = format!("{}::from_field_values", name);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let labels: Vec<&String> = edited.iter().map(|member| &member.label).collect();
+    let idents: Vec<&Ident> = edited.iter().map(|member| &member.ident).collect();
+    let skipped: Vec<&Ident> = skipped.iter().map(|member| &member.ident).collect();
+    let specs: Vec<TokenStream2> = edited.iter().map(|member| member.spec()).collect();
+
+    let values: Vec<TokenStream2> = edited.iter().map(|member| {
+        let ident = &member.ident;
+
+        if member.is_optional() {
+            quote!(self.#ident.as_ref().map(::std::string::ToString::to_string).unwrap_or_default())
+        } else {
+            quote!(::std::string::ToString::to_string(&self.#ident))
+        }
+    }).collect();
+
+    let parsers: Vec<TokenStream2> = edited.iter().map(|member| {
+        let label = &member.label;
+
+        if member.is_optional() {
+            quote!(::ncursesw::form::parse_optional_field_value(values, #label))
+        } else {
+            quote!(::ncursesw::form::parse_field_value(values, #label))
+        }
+    }).collect();
+
+    Ok(quote! {
+        impl #impl_generics ::ncursesw::form::FormStruct for #name #ty_generics #where_clause {
+            fn field_specs() -> ::std::vec::Vec<(::std::string::String, ::ncursesw::form::FieldSpec)> {
+                ::std::vec![#((::std::string::String::from(#labels), #specs)),*]
+            }
+
+            fn field_values(&self) -> ::std::vec::Vec<(::std::string::String, ::std::string::String)> {
+                ::std::vec![#((::std::string::String::from(#labels), #values)),*]
+            }
+
+            fn from_field_values(
+                values: &::std::collections::HashMap<::std::string::String, ::std::string::String>
+            ) -> ::std::result::Result<Self, ::ncursesw::form::NCurseswFormError> {
+                match (#(#parsers,)*) {
+                    (#(::std::result::Result::Ok(#idents),)*) => ::std::result::Result::Ok(Self {
+                        #(#idents,)*
+                        #(#skipped: ::std::default::Default::default(),)*
+                    }),
+                    (#(#idents,)*)                          => ::std::result::Result::Err(::ncursesw::form::NCurseswFormError::ParseFields {
+                        func:   ::std::string::String::from(#func),
+                        fields: [#(#idents.err()),*].into_iter().flatten().collect()
+                    })
+                }
+            }
+        }
+    })
+}
+
+#[test]
+fn expand_form_test() {
+    let input: DeriveInput = syn::parse_quote! {
+        struct Config {
+            #[form(label = "Host name", width = 30)]
+            host: String,
+            timeout: Option<u32>,
+            #[form(skip)]
+            cache: Vec<String>
+        }
+    };
+
+    let expected = quote! {
+        impl ::ncursesw::form::FormStruct for Config {
+            fn field_specs() -> ::std::vec::Vec<(::std::string::String, ::ncursesw::form::FieldSpec)> {
+                ::std::vec![
+                    (::std::string::String::from("Host name"), ::ncursesw::form::FieldSpec::text(30i32)),
+                    (::std::string::String::from("timeout"), ::ncursesw::form::FieldSpec::text(20i32))
+                ]
+            }
+
+            fn field_values(&self) -> ::std::vec::Vec<(::std::string::String, ::std::string::String)> {
+                ::std::vec![
+                    (::std::string::String::from("Host name"), ::std::string::ToString::to_string(&self.host)),
+                    (::std::string::String::from("timeout"), self.timeout.as_ref().map(::std::string::ToString::to_string).unwrap_or_default())
+                ]
+            }
+
+            fn from_field_values(
+                values: &::std::collections::HashMap<::std::string::String, ::std::string::String>
+            ) -> ::std::result::Result<Self, ::ncursesw::form::NCurseswFormError> {
+                match (
+                    ::ncursesw::form::parse_field_value(values, "Host name"),
+                    ::ncursesw::form::parse_optional_field_value(values, "timeout"),
+                ) {
+                    (::std::result::Result::Ok(host), ::std::result::Result::Ok(timeout),) => ::std::result::Result::Ok(Self {
+                        host,
+                        timeout,
+                        cache: ::std::default::Default::default(),
+                    }),
+                    (host, timeout,) => ::std::result::Result::Err(::ncursesw::form::NCurseswFormError::ParseFields {
+                        func:   ::std::string::String::from("Config::from_field_values"),
+                        fields: [host.err(), timeout.err()].into_iter().flatten().collect()
+                    })
+                }
+            }
+        }
+    };
+
+    assert_eq!(expand_form(&input).unwrap().to_string(), expected.to_string());
+}
+
+#[test]
+fn form_member_spec_test() {
+    let spec = |field: syn::Field| FormMember::parse(&field).unwrap().spec().to_string();
+
+    assert_eq!(
+        spec(syn::parse_quote!(#[form(password, mask = '*', width = 8)] pin: String)),
+        quote!(::ncursesw::form::FieldSpec::password(8i32, ::std::option::Option::Some('*'))).to_string()
+    );
+    assert_eq!(
+        spec(syn::parse_quote!(#[form(password)] secret: String)),
+        quote!(::ncursesw::form::FieldSpec::password(20i32, ::std::option::Option::None)).to_string()
+    );
+    assert_eq!(
+        spec(syn::parse_quote!(#[form(lines = 4, width = 40)] notes: String)),
+        quote!(::ncursesw::form::FieldSpec::multi_line(4i32, 40i32)).to_string()
+    );
+    assert_eq!(
+        spec(syn::parse_quote!(#[form(width = 5, validator = FieldType::port())] port: u16)),
+        quote!(::ncursesw::form::FieldSpec::text(5i32).set_field_type(FieldType::port())).to_string()
+    );
+}
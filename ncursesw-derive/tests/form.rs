@@ -0,0 +1,71 @@
+/*
+    ncursesw-derive/tests/form.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::collections::HashMap;
+use ncursesw::form::{Form, FormStruct, FieldType, NCurseswFormError};
+
+#[derive(Debug, Default, PartialEq, Form)]
+struct Config {
+    #[form(label = "Host name", width = 30)]
+    host:    String,
+    #[form(label = "Port", width = 5, validator = FieldType::port())]
+    port:    u16,
+    #[form(label = "Timeout", width = 5)]
+    timeout: Option<u32>,
+    #[form(skip)]
+    cache:   Vec<String>
+}
+
+#[test]
+fn form_round_trip_test() {
+    let labels: Vec<String> = Config::field_specs().into_iter().map(|(label, _)| label).collect();
+
+    assert_eq!(labels, ["Host name", "Port", "Timeout"]);
+
+    let config = Config { host: "localhost".to_string(), port: 8080, timeout: Some(30), cache: vec![] };
+    let values: HashMap<String, String> = config.field_values().into_iter().collect();
+
+    assert_eq!(values.get("Timeout").map(String::as_str), Some("30"));
+    assert_eq!(Config::from_field_values(&values).unwrap(), config);
+
+    // an empty optional field is `None` and a skipped member is its default.
+    let config = Config { host: "localhost".to_string(), port: 8080, timeout: None, cache: vec!["stale".to_string()] };
+    let values: HashMap<String, String> = config.field_values().into_iter().collect();
+
+    assert_eq!(values.get("Timeout").map(String::as_str), Some(""));
+    assert!(!values.contains_key("cache"));
+    assert_eq!(Config::from_field_values(&values).unwrap(), Config { cache: vec![], ..config });
+
+    // each value that can not be parsed is reported with its label.
+    let mut values = values;
+
+    values.insert("Port".to_string(), "http".to_string());
+    values.insert("Timeout".to_string(), "soon".to_string());
+
+    match Config::from_field_values(&values) {
+        Err(NCurseswFormError::ParseFields { func, fields }) => {
+            assert_eq!(func, "Config::from_field_values");
+            assert_eq!(fields.into_iter().map(|(label, _)| label).collect::<Vec<String>>(), ["Port", "Timeout"]);
+        },
+        result                                              => panic!("unexpected {:?}", result)
+    }
+}
@@ -0,0 +1,11 @@
+use ncursesw_derive::Form;
+
+#[derive(Form)]
+struct Login {
+    #[form(label = "Name")]
+    user:    String,
+    #[form(label = "Name")]
+    account: String
+}
+
+fn main() { }
@@ -0,0 +1,9 @@
+use ncursesw_derive::Form;
+
+#[derive(Form)]
+struct Login {
+    #[form(mask = '*')]
+    pin: String
+}
+
+fn main() { }
@@ -0,0 +1,9 @@
+use ncursesw_derive::Form;
+
+#[derive(Form)]
+struct Login {
+    #[form(password, lines = 2)]
+    secret: String
+}
+
+fn main() { }
@@ -0,0 +1,9 @@
+use ncursesw_derive::Form;
+
+#[derive(Form)]
+struct Login {
+    #[form(label = "Name", colour = "red")]
+    user: String
+}
+
+fn main() { }
@@ -0,0 +1,128 @@
+/*
+    src/colorpairlru.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{hash::Hash, collections::HashMap};
+
+// The bookkeeping of a `ColorPairPool` (see `normal::ColorPairPool` and
+// `extend::ColorPairPool`) kept apart from allocating and freeing the color
+// pairs, `K` is the combination of colors and `V` the color pair allocated
+// for it. The pool allocates and frees the color pairs this returns.
+#[derive(Debug)]
+pub(in crate) struct ColorPairLru<K, V> {
+    // the number of users (excluding clones) of each combination of colors.
+    users:     HashMap<K, usize>,
+    // the allocated color pairs and when each was last used.
+    allocated: HashMap<K, (V, u64)>,
+    clock:     u64
+}
+
+impl<K: Copy + Eq + Hash, V: Copy> ColorPairLru<K, V> {
+    pub(in crate) fn new() -> Self {
+        Self { users: HashMap::new(), allocated: HashMap::new(), clock: 0 }
+    }
+
+    // the number of allocated color pairs.
+    pub(in crate) fn len(&self) -> usize {
+        self.allocated.len()
+    }
+
+    // add a user of the colors.
+    pub(in crate) fn acquire(&mut self, key: K) {
+        *self.users.entry(key).or_insert(0) += 1;
+    }
+
+    // remove a user of the colors returning the color pair to free once the
+    // colors have no users.
+    pub(in crate) fn release(&mut self, key: K) -> Option<V> {
+        let users = self.users.get(&key).map_or(0, |users| users.saturating_sub(1));
+
+        if users == 0 {
+            self.users.remove(&key);
+            self.allocated.remove(&key).map(|(value, _)| value)
+        } else {
+            self.users.insert(key, users);
+
+            None
+        }
+    }
+
+    // the allocated color pair of the colors marking it as the most-recently used.
+    pub(in crate) fn touch(&mut self, key: K) -> Option<V> {
+        self.clock += 1;
+
+        let clock = self.clock;
+
+        self.allocated.get_mut(&key).map(|(value, last_used)| {
+            *last_used = clock;
+
+            *value
+        })
+    }
+
+    // record the color pair allocated for the colors as the most-recently used.
+    pub(in crate) fn insert(&mut self, key: K, value: V) {
+        self.clock += 1;
+        self.allocated.insert(key, (value, self.clock));
+    }
+
+    // remove the least-recently used color pair returning it to free.
+    pub(in crate) fn evict(&mut self) -> Option<V> {
+        let least_recently_used = self.allocated.iter().min_by_key(|(_, (_, last_used))| *last_used).map(|(key, _)| *key);
+
+        least_recently_used.and_then(|key| self.allocated.remove(&key)).map(|(value, _)| value)
+    }
+}
+
+#[test]
+fn color_pair_lru_test() {
+    let mut lru = ColorPairLru::new();
+
+    // the color pair is freed when the last user is released.
+    lru.acquire('a');
+    lru.insert('a', 1);
+    lru.acquire('a');
+
+    assert_eq!(lru.touch('a'), Some(1));
+    assert_eq!(lru.release('a'), None);
+    assert_eq!(lru.release('a'), Some(1));
+    assert_eq!(lru.len(), 0);
+    assert_eq!(lru.touch('a'), None);
+
+    // the least-recently used color pair is evicted, touching a color pair makes it the most-recently used.
+    for (key, value) in [('a', 1), ('b', 2), ('c', 3)] {
+        lru.acquire(key);
+        lru.insert(key, value);
+    }
+
+    assert_eq!(lru.touch('a'), Some(1));
+    assert_eq!(lru.evict(), Some(2));
+    assert_eq!(lru.evict(), Some(3));
+    assert_eq!(lru.len(), 1);
+
+    // an evicted color pair is not freed again when its users are released
+    // but is freed if it was allocated again.
+    assert_eq!(lru.release('b'), None);
+    lru.insert('c', 4);
+    assert_eq!(lru.release('c'), Some(4));
+    assert_eq!(lru.release('a'), Some(1));
+    assert_eq!(lru.evict(), None);
+}
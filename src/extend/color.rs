@@ -25,9 +25,8 @@
 use crate::{
     NCurseswError,
     gen::ColorType,
-    ncursescolortype::{set_ncurses_colortype, NCursesColorType},
     shims::ncurses::SCREEN,
-    extend::{ColorPalette, RGB},
+    extend::{ColorPalette, RGB, direct_color_bits, direct_color_bits_sp},
     ncurses::{
         init_extended_color, extended_color_content,
         init_extended_color_sp, extended_color_content_sp
@@ -45,8 +44,6 @@ impl Color {
     pub(in crate) fn _from(screen: Option<SCREEN>, color_palette: ColorPalette) -> Self {
         assert!(screen.map_or_else(|| true, |screen| !screen.is_null()), "Color::_from() : screen.is_null()");
 
-        set_ncurses_colortype(NCursesColorType::Extend);
-
         Self { screen, color_palette }
     }
 }
@@ -81,6 +78,29 @@ impl Color {
         self.color_palette
     }
 
+    /// Create a direct color from its red, green and blue content without
+    /// using a palette slot, the terminal must be a direct color terminal
+    /// (see `direct_color_bits()`) otherwise `NCurseswError::DirectColorUnsupported`
+    /// is returned.
+    ///
+    /// The red, green and blue content is scaled to the precision of the
+    /// terminal (usually 8 bits each) and packed into the color number.
+    pub fn direct(rgb: RGB) -> result!(Self) {
+        Self::_direct(None, rgb)
+    }
+
+    /// Screen function of `direct()`, the terminal of `screen` is used to
+    /// detect direct colors (see `direct_color_bits_sp()`).
+    pub fn direct_sp(screen: SCREEN, rgb: RGB) -> result!(Self) {
+        Self::_direct(Some(screen), rgb)
+    }
+
+    fn _direct(screen: Option<SCREEN>, rgb: RGB) -> result!(Self) {
+        let bits = screen.map_or_else(direct_color_bits, direct_color_bits_sp).ok_or(NCurseswError::DirectColorUnsupported)?;
+
+        Ok(Self::_from(screen, ColorPalette::_from(direct_color_number(rgb, bits))))
+    }
+
     pub fn set_rgb(&self, rgb: RGB) -> result!(()) {
         match self.screen {
             None         => init_extended_color(self.color_palette.number(), rgb),
@@ -96,6 +116,16 @@ impl Color {
     }
 }
 
+// pack the red, green and blue content of a color into a direct color number
+// with `bits` bits for each component. the first 8 colors of a direct color
+// terminal are its ansi colors so the number is at least 8.
+pub(in crate::extend) fn direct_color_number(rgb: RGB, bits: i32) -> i32 {
+    let maximum = (1 << bits) - 1;
+    let scale = |content: i32| (content * maximum + 500) / 1000;
+
+    ((scale(rgb.red()) << (bits * 2)) | (scale(rgb.green()) << bits) | scale(rgb.blue())).max(8)
+}
+
 impl Default for Color {
     fn default() -> Self {
         Self::_from(None, ColorPalette::default())
@@ -107,3 +137,11 @@ impl ColorType<i32> for Color {
         self.color_palette.number()
     }
 }
+
+#[test]
+fn direct_color_number_test() {
+    assert_eq!(direct_color_number(RGB::new(1000, 500, 0), 8), 0xff8000);
+    assert_eq!(direct_color_number(RGB::new(1000, 1000, 1000), 8), 0xffffff);
+    assert_eq!(direct_color_number(RGB::new(0, 0, 0), 8), 8);
+    assert_eq!(direct_color_number(RGB::new(1000, 0, 1000), 4), 0xf0f);
+}
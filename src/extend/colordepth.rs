@@ -0,0 +1,215 @@
+/*
+    src/extend/colordepth.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+#![allow(deprecated)]
+
+use std::convert::TryFrom;
+use crate::{
+    SCREEN, NCurseswError, COLORS, COLORS_sp, can_change_color, can_change_color_sp, has_colors, has_colors_sp,
+    extended_color_content, extended_color_content_sp,
+    extend::{Color, ColorPalette, RGB, direct_color_bits, direct_color_bits_sp, color::direct_color_number}
+};
+
+// the levels of each component of the xterm 256 color 6x6x6 color cube.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+// the default xterm definition of the 16 ansi colors.
+const ANSI_COLORS: [(u8, u8, u8); 16] = [
+    (0, 0, 0),       (205, 0, 0),   (0, 205, 0),   (205, 205, 0),
+    (0, 0, 238),     (205, 0, 205), (0, 205, 205), (229, 229, 229),
+    (127, 127, 127), (255, 0, 0),   (0, 255, 0),   (255, 255, 0),
+    (92, 92, 255),   (255, 0, 255), (0, 255, 255), (255, 255, 255)
+];
+
+/// The colors a terminal can display, used to degrade an `RGB` color to the
+/// nearest color the terminal supports (see `degrade_colors()`).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ColorDepth {
+    /// The terminal has no colors, attributes are used in place of colors.
+    Monochrome,
+    /// The terminal has the 8 ansi colors.
+    Ansi8,
+    /// The terminal has the 8 ansi colors and their light variants.
+    Ansi16,
+    /// The terminal has the xterm 256 color palette.
+    Xterm256,
+    /// The terminal is a direct color terminal (see `direct_color_bits()`).
+    Direct
+}
+
+impl ColorDepth {
+    /// Detect the color depth of the terminal using `has_colors()`,
+    /// `direct_color_bits()` and `COLORS()`.
+    pub fn detect() -> Self {
+        Self::_detect(None)
+    }
+
+    /// Screen function of `detect()`, using `has_colors_sp()`, `direct_color_bits_sp()`
+    /// and `COLORS_sp()`.
+    pub fn detect_sp(screen: SCREEN) -> Self {
+        Self::_detect(Some(screen))
+    }
+
+    pub(in crate) fn _detect(screen: Option<SCREEN>) -> Self {
+        if !screen.map_or_else(has_colors, has_colors_sp) {
+            ColorDepth::Monochrome
+        } else if screen.map_or_else(direct_color_bits, direct_color_bits_sp).is_some() {
+            ColorDepth::Direct
+        } else {
+            match screen.map_or_else(COLORS, COLORS_sp) {
+                colors if colors >= 256 => ColorDepth::Xterm256,
+                colors if colors >= 16  => ColorDepth::Ansi16,
+                colors if colors >= 8   => ColorDepth::Ansi8,
+                _                       => ColorDepth::Monochrome
+            }
+        }
+    }
+
+    /// Returns the nearest color of this color depth, or `None` for a monochrome terminal.
+    ///
+    /// If the terminal can change the definition of its colors (see `can_change_color()`)
+    /// the ansi colors are compared using their current definition, otherwise the
+    /// default xterm definition is used.
+    pub fn nearest_color(self, rgb: RGB) -> result!(Option<Color>) {
+        self._nearest_color(None, rgb)
+    }
+
+    /// Screen function of `nearest_color()`.
+    pub fn nearest_color_sp(self, screen: SCREEN, rgb: RGB) -> result!(Option<Color>) {
+        self._nearest_color(Some(screen), rgb)
+    }
+
+    fn _nearest_color(self, screen: Option<SCREEN>, rgb: RGB) -> result!(Option<Color>) {
+        Ok(self.nearest_number(screen, rgb)?.map(|number| Color::_from(screen, ColorPalette::_from(number))))
+    }
+
+    // the number of the nearest color, creating no colors so that the crate's
+    // color type (see `ncurses_colortype()`) is unchanged.
+    pub(in crate) fn nearest_number(self, screen: Option<SCREEN>, rgb: RGB) -> result!(Option<i32>) {
+        let number = match self {
+            ColorDepth::Monochrome => return Ok(None),
+            ColorDepth::Direct     => {
+                let bits = screen.map_or_else(direct_color_bits, direct_color_bits_sp).ok_or(NCurseswError::DirectColorUnsupported)?;
+
+                return Ok(Some(direct_color_number(rgb, bits)));
+            },
+            ColorDepth::Xterm256   => nearest_xterm256(rgb.to_rgb8()),
+            ColorDepth::Ansi8      => nearest(rgb.to_rgb8(), &ansi_colors(screen, 8)?),
+            ColorDepth::Ansi16     => nearest(rgb.to_rgb8(), &ansi_colors(screen, 16)?)
+        };
+
+        Ok(Some(i32::try_from(number)?))
+    }
+}
+
+// the definition of the first `count` ansi colors.
+fn ansi_colors(screen: Option<SCREEN>, count: usize) -> result!(Vec<(u8, u8, u8)>) {
+    let can_change_color = screen.map_or_else(can_change_color, can_change_color_sp);
+
+    if can_change_color {
+        (0..i32::try_from(count)?)
+            .map(|number| {
+                let rgb = match screen {
+                    None         => extended_color_content(number)?,
+                    Some(screen) => extended_color_content_sp(screen, number)?
+                };
+
+                Ok(rgb.to_rgb8())
+            })
+            .collect()
+    } else {
+        Ok(ANSI_COLORS[..count].to_vec())
+    }
+}
+
+// the index of the nearest color.
+fn nearest(rgb: (u8, u8, u8), colors: &[(u8, u8, u8)]) -> usize {
+    colors.iter().enumerate().min_by_key(|(_, color)| distance(rgb, **color)).map_or(0, |(index, _)| index)
+}
+
+// the nearest color of the xterm 256 color palette from either the color
+// cube (colors 16 to 231) or the grey scale (colors 232 to 255).
+fn nearest_xterm256(rgb: (u8, u8, u8)) -> usize {
+    let level = |content: u8| nearest((content, content, content), &CUBE_LEVELS.map(|level| (level, level, level)));
+
+    let (red, green, blue) = (level(rgb.0), level(rgb.1), level(rgb.2));
+    let cube = (CUBE_LEVELS[red], CUBE_LEVELS[green], CUBE_LEVELS[blue]);
+
+    let average = (u16::from(rgb.0) + u16::from(rgb.1) + u16::from(rgb.2)) / 3;
+    let grey = usize::from(average.saturating_sub(3) / 10).min(23);
+    let grey_level = 8 + 10 * grey as u8;
+
+    if distance(rgb, (grey_level, grey_level, grey_level)) < distance(rgb, cube) {
+        232 + grey
+    } else {
+        16 + 36 * red + 6 * green + blue
+    }
+}
+
+// the weighted ("redmean") distance between two colors.
+fn distance(left: (u8, u8, u8), right: (u8, u8, u8)) -> i32 {
+    let mean = (i32::from(left.0) + i32::from(right.0)) / 2;
+    let (red, green, blue) = (
+        i32::from(left.0) - i32::from(right.0),
+        i32::from(left.1) - i32::from(right.1),
+        i32::from(left.2) - i32::from(right.2)
+    );
+
+    (((512 + mean) * red * red) >> 8) + 4 * green * green + (((767 - mean) * blue * blue) >> 8)
+}
+
+#[test]
+fn color_depth_test() {
+    assert_eq!(nearest_xterm256((255, 0, 0)), 196);
+    assert_eq!(nearest_xterm256((0, 0, 0)), 16);
+    assert_eq!(nearest_xterm256((255, 255, 255)), 231);
+    assert_eq!(nearest_xterm256((128, 128, 128)), 244);
+    assert_eq!(nearest_xterm256((255, 135, 0)), 208);
+    assert_eq!(nearest((250, 10, 10), &ANSI_COLORS), 9);
+    assert_eq!(nearest((120, 10, 10), &ANSI_COLORS[..8]), 1);
+    assert_eq!(nearest((40, 40, 40), &ANSI_COLORS), 0);
+}
+
+#[test]
+fn color_depth_detect_sp_test() {
+    use std::fs::OpenOptions;
+    use crate::{newterm, set_term, delscreen, endwin, start_color_sp, stdscr, shims::bindings};
+
+    crate::funcs::with_test_screen(|first| {
+        start_color_sp(first).unwrap();
+
+        let terminal = OpenOptions::new().read(true).write(true).open("/dev/null").unwrap();
+        let second = newterm(None::<String>, &terminal, &terminal).unwrap();
+
+        // colors have only been started on the first screen.
+        assert_eq!(ColorDepth::detect_sp(first), ColorDepth::Ansi8);
+        assert_eq!(ColorDepth::detect_sp(second), ColorDepth::Monochrome);
+        assert_eq!(ColorDepth::detect(), ColorDepth::Monochrome);
+
+        // the current screen is restored.
+        assert_eq!(unsafe { bindings::_nc_screen_of(stdscr()) }, second);
+
+        let _ = endwin();
+        delscreen(second);
+        let _ = set_term(first);
+    });
+}
@@ -26,7 +26,6 @@ use std::ptr;
 use crate::{
     NCurseswError,
     gen::{ColorPairType, ColorPairGeneric, ColorPairColors, ColorsType},
-    ncursescolortype::{set_ncurses_colortype, NCursesColorType},
     shims::ncurses::SCREEN,
     extend::{Colors, Color},
     ncurses::{
@@ -46,8 +45,6 @@ impl ColorPair {
     pub(in crate) fn _from(screen: Option<SCREEN>, number: i32) -> Self {
         assert!(screen.map_or_else(|| true, |screen| !screen.is_null()), "ColorPair::_from() : screen.is_null()");
 
-        set_ncurses_colortype(NCursesColorType::Extend);
-
         Self { screen, number }
     }
 }
@@ -0,0 +1,55 @@
+/*
+    src/extend/colorpairpool.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+#![allow(deprecated)]
+
+use crate::extend::{ColorPair, Colors, alloc_pair, alloc_pair_sp};
+
+include!("../include/colorpairpool.rs");
+
+define_color_pair_pool!(i32::MAX);
+
+#[test]
+fn color_pair_pool_capacity_test() {
+    use std::fs::OpenOptions;
+    use crate::{newterm, set_term, delscreen, endwin, start_color_sp};
+
+    crate::funcs::with_test_screen(|first| {
+        start_color_sp(first).unwrap();
+
+        let terminal = OpenOptions::new().read(true).write(true).open("/dev/null").unwrap();
+
+        std::env::set_var("TERM", "xterm-256color");
+
+        let second = newterm(None::<String>, &terminal, &terminal).unwrap();
+
+        start_color_sp(second).unwrap();
+
+        // the capacity is that of the pool's screen rather than the current screen.
+        assert_eq!(ColorPairPool::new_sp(first).capacity().unwrap(), usize::try_from(crate::COLOR_PAIRS_sp(first) - 1).unwrap());
+        assert_ne!(ColorPairPool::new_sp(first).capacity().unwrap(), ColorPairPool::new().capacity().unwrap());
+
+        let _ = endwin();
+        delscreen(second);
+        let _ = set_term(first);
+    });
+}
@@ -22,10 +22,12 @@
 
 use std::ptr;
 use crate::{
-    SCREEN, NCurseswError,
+    SCREEN, NCurseswError, has_colors, has_colors_sp, tigetflag, tigetflag_sp, tigetnum, tigetnum_sp, tigetstr, tigetstr_sp,
     gen::{ColorType, ColorsType},
+    ncursescolortype::{set_screen_colortype, NCursesColorType},
     shims::ncurses,
-    extend::{ColorPair, Colors}
+    shims::constants::A_NORMAL,
+    extend::{Attributes, AttributesColorPair, Color, ColorDepth, ColorPair, Colors, RGB}
 };
 
 /// Accepts a parameter for foreground and background color, and checks if
@@ -43,6 +45,8 @@ use crate::{
 pub fn alloc_pair(colors: Colors) -> result!(ColorPair) {
     assert!(colors.screen().is_none(), "alloc_pair() : colors.screen().is_some()");
 
+    set_screen_colortype(None, NCursesColorType::Extend)?;
+
     let number = ncurses::alloc_pair(colors.foreground().number(), colors.background().number());
 
     if number.is_negative() {
@@ -70,6 +74,8 @@ pub fn find_pair(colors: Colors) -> Option<ColorPair> {
 pub fn alloc_pair_sp(screen: SCREEN, colors: Colors) -> result!(ColorPair) {
     assert!(colors.screen().map_or_else(|| false, |colors_scr| ptr::eq(screen, colors_scr)), "alloc_pair_sp() : screen.is_null() || screen != colors.screen()");
 
+    set_screen_colortype(Some(screen), NCursesColorType::Extend)?;
+
     let number = unsafe { ncurses::alloc_pair_sp(screen, colors.foreground().number(), colors.background().number()) };
 
     if number.is_negative() {
@@ -91,3 +97,124 @@ pub fn find_pair_sp(screen: SCREEN, colors: Colors) -> Option<ColorPair> {
         Some(ColorPair::_from(Some(screen), number))
     }
 }
+
+/// Returns the number of bits of each of the red, green and blue components of
+/// a direct color, or `None` if the terminal is not a direct color terminal.
+///
+/// A direct color terminal (i.e. `TERM=xterm-direct`) has the terminfo `RGB`
+/// capability, NCurses (v6.1 and later) then treats color numbers as packed
+/// red, green and blue content rather than palette slots. The `RGB` capability
+/// is either a number giving the bits of each component, a string giving the
+/// bits of the red, green and blue components (i.e. "8/8/8", the components
+/// must have the same number of bits) or a flag in which case the bits are
+/// derived from the terminfo `colors` capability.
+pub fn direct_color_bits() -> Option<i32> {
+    _direct_color_bits(None)
+}
+
+/// Screen function of `direct_color_bits()`.
+pub fn direct_color_bits_sp(screen: SCREEN) -> Option<i32> {
+    _direct_color_bits(Some(screen))
+}
+
+fn _direct_color_bits(screen: Option<SCREEN>) -> Option<i32> {
+    if !screen.map_or_else(has_colors, has_colors_sp) {
+        return None;
+    }
+
+    let number = |capname| screen.map_or_else(|| tigetnum(capname), |screen| tigetnum_sp(screen, capname)).ok().flatten();
+    let flag = |capname| screen.map_or_else(|| tigetflag(capname), |screen| tigetflag_sp(screen, capname)).unwrap_or(false);
+    let string = |capname| screen.map_or_else(|| tigetstr(capname), |screen| tigetstr_sp(screen, capname)).ok().flatten();
+
+    let bits = if let Some(bits) = number("RGB") {
+        bits
+    } else if flag("RGB") {
+        number("colors").and_then(i32::checked_ilog2).map_or(0, |width| width as i32 / 3)
+    } else {
+        string("RGB").and_then(|rgb| rgb_bits(&rgb)).unwrap_or(0)
+    };
+
+    if (1..=10).contains(&bits) {
+        Some(bits)
+    } else {
+        None
+    }
+}
+
+// the bits of each component of a `RGB` string capability (i.e. "8/8/8").
+fn rgb_bits(rgb: &str) -> Option<i32> {
+    let bits = rgb.split('/').map(|bits| bits.trim().parse::<i32>().ok()).collect::<Option<Vec<i32>>>()?;
+
+    match bits.as_slice() {
+        [red, green, blue] if red == green && green == blue => Some(*red),
+        _                                                   => None
+    }
+}
+
+/// Returns `true` if the terminal is a direct color terminal (see `direct_color_bits()`).
+pub fn has_direct_color() -> bool {
+    direct_color_bits().is_some()
+}
+
+/// Screen function of `has_direct_color()`.
+pub fn has_direct_color_sp(screen: SCREEN) -> bool {
+    direct_color_bits_sp(screen).is_some()
+}
+
+/// Allocate a color pair of direct colors (see `Color::direct()` and `alloc_pair()`),
+/// when the table of color pairs fills up the least-recently allocated pair is reused.
+pub fn alloc_direct_pair(foreground: RGB, background: RGB) -> result!(ColorPair) {
+    alloc_pair(Colors::new(Color::direct(foreground)?, Color::direct(background)?))
+}
+
+/// Screen function of `alloc_direct_pair()`.
+pub fn alloc_direct_pair_sp(screen: SCREEN, foreground: RGB, background: RGB) -> result!(ColorPair) {
+    alloc_pair_sp(screen, Colors::new(Color::direct_sp(screen, foreground)?, Color::direct_sp(screen, background)?))
+}
+
+/// Degrade a foreground and background color to the nearest colors the
+/// terminal supports (see `ColorDepth::detect()` and `ColorDepth::nearest_color()`)
+/// and allocate a color pair of them (see `alloc_pair()`).
+///
+/// On a monochrome terminal attributes are used in place of colors, the
+/// attributes are reversed if the background is lighter than the foreground
+/// otherwise they are bold if the foreground is light.
+pub fn degrade_colors(foreground: RGB, background: RGB) -> result!(AttributesColorPair) {
+    let depth = ColorDepth::detect();
+
+    match (depth.nearest_color(foreground)?, depth.nearest_color(background)?) {
+        (Some(foreground), Some(background)) => Ok(AttributesColorPair::new(Attributes::default(), alloc_pair(Colors::new(foreground, background))?)),
+        _                                    => Ok(AttributesColorPair::new(monochrome_attributes(Attributes::default(), foreground, background), ColorPair::default()))
+    }
+}
+
+/// Screen function of `degrade_colors()`.
+pub fn degrade_colors_sp(screen: SCREEN, foreground: RGB, background: RGB) -> result!(AttributesColorPair) {
+    let depth = ColorDepth::detect_sp(screen);
+    let attributes = Attributes::new_sp(screen, A_NORMAL);
+
+    match (depth.nearest_color_sp(screen, foreground)?, depth.nearest_color_sp(screen, background)?) {
+        (Some(foreground), Some(background)) => Ok(AttributesColorPair::new(attributes, alloc_pair_sp(screen, Colors::new(foreground, background))?)),
+        _                                    => Ok(AttributesColorPair::new(monochrome_attributes(attributes, foreground, background), ColorPair::_from(Some(screen), 0)))
+    }
+}
+
+// the attributes used in place of a foreground and background color.
+fn monochrome_attributes(attributes: Attributes, foreground: RGB, background: RGB) -> Attributes {
+    let (foreground, background) = (foreground.relative_luminance(), background.relative_luminance());
+
+    if background > foreground {
+        attributes.set_reverse(true)
+    } else {
+        attributes.set_bold(foreground >= 0.5)
+    }
+}
+
+#[test]
+fn rgb_bits_test() {
+    assert_eq!(rgb_bits("8/8/8"), Some(8));
+    assert_eq!(rgb_bits("10/10/10"), Some(10));
+    assert_eq!(rgb_bits("5/6/5"), None);
+    assert_eq!(rgb_bits("8"), None);
+    assert_eq!(rgb_bits("8/x/8"), None);
+}
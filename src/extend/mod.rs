@@ -24,14 +24,16 @@ mod attribute;
 mod attributes;
 mod attributescolorpair;
 mod color;
+mod colordepth;
 mod colorpalette;
 mod colorpair;
+mod colorpairpool;
 mod colors;
 mod funcs;
 mod rgb;
 
 pub use self::{
-    attribute::*, attributes::*, attributescolorpair::*,
-    color::*, colorpalette::*, colorpair::*, colors::*,
-    funcs::*, rgb::*
+    attribute::*, attributes::*, attributescolorpair::*, color::*,
+    colordepth::*, colorpalette::*, colorpair::*, colorpairpool::*,
+    colors::*, funcs::*, rgb::*
 };
@@ -0,0 +1,170 @@
+/*
+    src/form/fieldchecks.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+// The checks used by the ready-made field types of `FieldType`.
+
+use std::{net::Ipv6Addr, path::Path, str::FromStr};
+
+// `digits` is exactly `count` ascii digits.
+fn is_digits(digits: &str, count: usize) -> bool {
+    digits.len() == count && digits.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+// an ISO 8601 calendar date (YYYY-MM-DD).
+pub(in crate::form) fn is_iso_date(value: &str) -> bool {
+    let parts: Vec<&str> = value.split('-').collect();
+
+    if parts.len() != 3 || !is_digits(parts[0], 4) || !is_digits(parts[1], 2) || !is_digits(parts[2], 2) {
+        return false;
+    }
+
+    let (year, month, day): (u32, u32, u32) = (parts[0].parse().unwrap_or(0), parts[1].parse().unwrap_or(0), parts[2].parse().unwrap_or(0));
+    let leap_year = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11              => 30,
+        2 if leap_year              => 29,
+        2                           => 28,
+        _                           => return false
+    };
+
+    (1..=days).contains(&day)
+}
+
+// a 24 hour time (HH:MM or HH:MM:SS).
+pub(in crate::form) fn is_time(value: &str) -> bool {
+    let parts: Vec<&str> = value.split(':').collect();
+
+    (parts.len() == 2 || parts.len() == 3) && parts.iter().enumerate().all(|(i, part)| {
+        is_digits(part, 2) && part.parse::<u32>().is_ok_and(|n| n < if i == 0 { 24 } else { 60 })
+    })
+}
+
+// an e-mail address (local-part@domain) where the domain has at least two labels.
+pub(in crate::form) fn is_email(value: &str) -> bool {
+    let (local, domain) = match value.rsplit_once('@') {
+        Some(parts) => parts,
+        None        => return false
+    };
+
+    let local_valid = !local.is_empty()
+        && local.len() <= 64
+        && !local.starts_with('.')
+        && !local.ends_with('.')
+        && !local.contains("..")
+        && local.chars().all(|ch| ch.is_ascii_alphanumeric() || "!#$%&'*+/=?^_`{|}~.-".contains(ch));
+
+    let labels: Vec<&str> = domain.split('.').collect();
+
+    let domain_valid = labels.len() >= 2 && labels.iter().all(|label| {
+        !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '-')
+    });
+
+    local_valid && domain_valid
+}
+
+// a hexadecimal number with an optional "0x" prefix.
+pub(in crate::form) fn is_hex(value: &str) -> bool {
+    let digits = value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")).unwrap_or(value);
+
+    !digits.is_empty() && digits.chars().all(|ch| ch.is_ascii_hexdigit())
+}
+
+// an IPv6 address.
+pub(in crate::form) fn is_ipv6(value: &str) -> bool {
+    Ipv6Addr::from_str(value).is_ok()
+}
+
+// a TCP/UDP port number (1 to 65535).
+pub(in crate::form) fn is_port(value: &str) -> bool {
+    !value.is_empty() && value.len() <= 5 && value.bytes().all(|byte| byte.is_ascii_digit()) && value.parse::<u16>().is_ok_and(|port| port > 0)
+}
+
+// a decimal number with an optional sign and at most `precision` decimal places.
+pub(in crate::form) fn is_float(value: &str, precision: usize) -> bool {
+    let unsigned = value.strip_prefix(['+', '-']).unwrap_or(value);
+    let (integer, fraction) = unsigned.split_once('.').map_or((unsigned, None), |(integer, fraction)| (integer, Some(fraction)));
+
+    let integer_valid = integer.bytes().all(|byte| byte.is_ascii_digit());
+    let fraction_valid = fraction.is_none_or(|fraction| {
+        precision > 0 && fraction.len() <= precision && fraction.bytes().all(|byte| byte.is_ascii_digit())
+    });
+    let has_digits = !integer.is_empty() || fraction.is_some_and(|fraction| !fraction.is_empty());
+
+    integer_valid && fraction_valid && has_digits
+}
+
+// a file path, if `must_exist` is `true` then the path must exist.
+pub(in crate::form) fn is_path(value: &str, must_exist: bool) -> bool {
+    !value.is_empty() && !value.chars().any(char::is_control) && (!must_exist || Path::new(value).exists())
+}
+
+#[test]
+fn field_checks_test() {
+    assert!(is_iso_date("2024-02-29"));
+    assert!(!is_iso_date("2023-02-29"));
+    assert!(!is_iso_date("1900-02-29"));
+    assert!(!is_iso_date("2022-13-01"));
+    assert!(!is_iso_date("2022-1-01"));
+
+    assert!(is_time("23:59"));
+    assert!(is_time("00:00:59"));
+    assert!(!is_time("24:00"));
+    assert!(!is_time("12:60:00"));
+
+    assert!(is_email("first.last+tag@mail.example.com"));
+    assert!(!is_email("first..last@example.com"));
+    assert!(!is_email("first@localhost"));
+    assert!(!is_email("first@-example.com"));
+
+    assert!(is_hex("0xDEADbeef"));
+    assert!(is_hex("ff"));
+    assert!(!is_hex("0x"));
+    assert!(!is_hex("0xfg"));
+
+    assert!(is_ipv6("::1"));
+    assert!(is_ipv6("2001:db8::ff00:42:8329"));
+    assert!(is_ipv6("::ffff:192.0.2.128"));
+    assert!(!is_ipv6("2001:db8:::1"));
+
+    assert!(is_port("65535"));
+    assert!(!is_port("0"));
+    assert!(!is_port("65536"));
+    assert!(!is_port("+80"));
+
+    assert!(is_float("-12.34", 2));
+    assert!(is_float(".5", 2));
+    assert!(is_float("7", 0));
+    assert!(!is_float("1.234", 2));
+    assert!(!is_float("1.5", 0));
+    assert!(!is_float("-", 2));
+    assert!(!is_float("1e5", 2));
+
+    assert!(is_path("/tmp/does/not/matter", false));
+    assert!(is_path("/", true));
+    assert!(!is_path("", false));
+}
@@ -0,0 +1,417 @@
+/*
+    src/form/fieldcompleter.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{fmt, ptr, convert::TryFrom};
+use crate::{
+    normal,
+    COLS, ChtypeChar, CharacterResult, GetCharacterType, KeyBinding, LINES, NCurseswError,
+    Origin, Size, delwin, getbegyx, getmaxx, getmaxyx, mvwaddstr, newwin, r#box, werase,
+    wattroff, wattron, wresize,
+    funcs::str_width,
+    shims::ncurses::WINDOW,
+    panels::{PANEL, del_panel, move_panel, new_panel, top_panel},
+    form::{
+        FIELD, FORM, FormInputResult, FormKeyMap, FormRequest, current_field, field_info,
+        form_handle_input, form_sub, set_field_value,
+        fieldvalue::current_field_value, forminput::form_request
+    }
+};
+
+type CandidatesFn = Box<dyn Fn(&str) -> Vec<String>>;
+
+enum CandidateSource {
+    List(Vec<String>),
+    Closure(CandidatesFn)
+}
+
+/// An autocomplete dropdown for a form field.
+///
+/// As the user types into the field the candidates matching the contents of
+/// the field are displayed in a panel below the field, the candidates are
+/// either those of a list that start with the contents of the field (ignoring
+/// case) or those returned by a closure.
+///
+/// While the dropdown is displayed the up and down arrows select a candidate,
+/// enter sets the field to the selected candidate and escape closes the
+/// dropdown, all other input is passed to `form_handle_input()`.
+///
+/// As the dropdown uses a panel `update_panels()` and `doupdate()` should be
+/// called after processing an input event to update the screen (see `Form::add_completer()`).
+///
+/// ```text
+/// let completer = FieldCompleter::new(field, vec!["Amsterdam", "Berlin", "Brussels"])
+///     .set_max_candidates(5);
+///
+/// loop {
+///     match completer.handle_input(form, wget_wch(window)?, &keymap)? {
+///         FormInputResult::Submitted => break,
+///         _                          => { }
+///     }
+///
+///     update_panels();
+///     doupdate()?;
+/// }
+/// ```
+pub struct FieldCompleter {
+    field:          FIELD,
+    source:         CandidateSource,
+    max_candidates: usize,
+    highlight:      normal::Attributes,
+    candidates:     Vec<String>,
+    selected:       usize,
+    window:         WINDOW,
+    panel:          PANEL
+}
+
+impl FieldCompleter {
+    /// An autocomplete dropdown of the candidates of a list.
+    pub fn new<S: Into<String>>(field: FIELD, candidates: Vec<S>) -> Self {
+        Self::with_source(field, CandidateSource::List(candidates.into_iter().map(Into::into).collect()))
+    }
+
+    /// An autocomplete dropdown of the candidates returned by a closure which
+    /// is passed the contents of the field.
+    pub fn with_closure<F: Fn(&str) -> Vec<String> + 'static>(field: FIELD, candidates: F) -> Self {
+        Self::with_source(field, CandidateSource::Closure(Box::new(candidates)))
+    }
+
+    fn with_source(field: FIELD, source: CandidateSource) -> Self {
+        Self {
+            field,
+            source,
+            max_candidates: 8,
+            highlight:      normal::Attributes::default() | normal::Attribute::Reverse,
+            candidates:     vec![],
+            selected:       0,
+            window:         ptr::null_mut(),
+            panel:          ptr::null_mut()
+        }
+    }
+
+    /// Set the maximum number of candidates displayed, the default is 8.
+    pub fn set_max_candidates(mut self, max_candidates: usize) -> Self {
+        self.max_candidates = max_candidates.max(1);
+
+        self
+    }
+
+    /// Set the attributes of the selected candidate.
+    pub fn set_highlight(mut self, attrs: normal::Attributes) -> Self {
+        self.highlight = attrs;
+
+        self
+    }
+
+    /// The field of the dropdown.
+    pub fn field(&self) -> FIELD {
+        self.field
+    }
+
+    /// Is the dropdown displayed.
+    pub fn is_open(&self) -> bool {
+        !self.panel.is_null()
+    }
+
+    /// The candidates matching a value.
+    pub fn candidates(&self, value: &str) -> Vec<String> {
+        let mut candidates = match &self.source {
+            CandidateSource::List(candidates) => {
+                let value = value.to_lowercase();
+
+                candidates.iter().filter(|candidate| candidate.to_lowercase().starts_with(&value)).cloned().collect()
+            },
+            CandidateSource::Closure(candidates) => candidates(value)
+        };
+
+        candidates.truncate(self.max_candidates);
+
+        candidates
+    }
+
+    /// Process an input event for a posted form, if the field of the dropdown
+    /// is not the current field then the event is passed to `form_handle_input()`.
+    pub fn handle_input<E>(&mut self, form: FORM, event: CharacterResult<E>, keymap: &FormKeyMap) -> result!(FormInputResult)
+        where E:    GetCharacterType + Copy,
+              char: TryFrom<E>
+    {
+        if current_field(Some(form))? != self.field {
+            self.close()?;
+
+            return Ok(form_handle_input(form, event, keymap)?);
+        }
+
+        if self.is_open() {
+            let key = match event {
+                CharacterResult::Key(key)      => Some(key),
+                CharacterResult::Character(ch) => match char::try_from(ch) {
+                    Ok('\n') | Ok('\r') => Some(KeyBinding::Enter),
+                    Ok('\x1b')          => Some(KeyBinding::Cancel),
+                    _                   => None
+                }
+            };
+
+            match key {
+                Some(KeyBinding::UpArrow)   => return self.select(self.selected.checked_sub(1).unwrap_or(self.candidates.len() - 1)),
+                Some(KeyBinding::DownArrow) => return self.select((self.selected + 1) % self.candidates.len()),
+                Some(KeyBinding::Enter)     => return self.complete(form),
+                Some(KeyBinding::Cancel)    => {
+                    self.close()?;
+
+                    return Ok(FormInputResult::Continue);
+                },
+                _                           => { }
+            }
+        }
+
+        let result = form_handle_input(form, event, keymap)?;
+
+        if current_field(Some(form))? == self.field {
+            self.refresh(form)?;
+        } else {
+            self.close()?;
+        }
+
+        Ok(result)
+    }
+
+    /// Close the dropdown.
+    pub fn close(&mut self) -> result!(()) {
+        if !self.panel.is_null() {
+            del_panel(self.panel)?;
+            self.panel = ptr::null_mut();
+        }
+
+        if !self.window.is_null() {
+            delwin(self.window)?;
+            self.window = ptr::null_mut();
+        }
+
+        self.candidates.clear();
+
+        Ok(())
+    }
+
+    // update the candidates from the contents of the field and redisplay the
+    // dropdown, the field is read without validating it as validation may
+    // change the contents of the field.
+    fn refresh(&mut self, form: FORM) -> result!(()) {
+        let value = current_field_value(form, self.field)?;
+        let candidates = if value.is_empty() { vec![] } else { self.candidates(&value) };
+
+        // there is nothing to complete if the only candidate has been typed in full (ignoring case).
+        if candidates.is_empty() || (candidates.len() == 1 && candidates[0].to_lowercase() == value.to_lowercase()) {
+            return self.close();
+        }
+
+        if self.is_open() && candidates == self.candidates {
+            return Ok(());
+        }
+
+        let parameters = field_info(self.field)?;
+        let begin = getbegyx(form_sub(Some(form))?)?;
+        let width = candidates.iter().map(|candidate| str_width(candidate)).max().unwrap_or(0);
+        let size = Size {
+            lines:   i32::try_from(candidates.len())? + 2,
+            columns: i32::try_from(width)?.max(parameters.size().columns) + 2
+        };
+
+        // below the field, or above it if there is no room.
+        let mut origin = Origin {
+            y: begin.y + parameters.origin().y + parameters.size().lines,
+            x: (begin.x + parameters.origin().x - 1).min(COLS() - size.columns).max(0)
+        };
+
+        if origin.y + size.lines > LINES() {
+            origin.y = (begin.y + parameters.origin().y - size.lines).max(0);
+        }
+
+        if self.is_open() {
+            if getmaxyx(self.window)? != size {
+                wresize(self.window, size)?;
+            }
+
+            move_panel(self.panel, origin)?;
+            werase(self.window)?;
+        } else {
+            self.window = newwin(size, origin)?;
+            self.panel = new_panel(self.window)?;
+        }
+
+        // keep the selected candidate if it is still a candidate.
+        self.selected = self.candidates.get(self.selected)
+            .and_then(|selected| candidates.iter().position(|candidate| candidate == selected))
+            .unwrap_or(0);
+        self.candidates = candidates;
+
+        r#box(self.window, ChtypeChar::from(0), ChtypeChar::from(0))?;
+        top_panel(self.panel)?;
+
+        self.draw()
+    }
+
+    fn draw(&self) -> result!(()) {
+        for (index, candidate) in self.candidates.iter().enumerate() {
+            let selected = index == self.selected;
+
+            if selected {
+                wattron(self.window, self.highlight)?;
+            }
+
+            // pad the candidate to the width of the dropdown in screen columns.
+            let padding = usize::try_from(getmaxx(self.window)? - 2)?.saturating_sub(str_width(candidate));
+
+            mvwaddstr(self.window, Origin { y: i32::try_from(index)? + 1, x: 1 }, format!("{}{}", candidate, " ".repeat(padding)))?;
+
+            if selected {
+                wattroff(self.window, self.highlight)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn select(&mut self, selected: usize) -> result!(FormInputResult) {
+        self.selected = selected;
+        self.draw()?;
+
+        Ok(FormInputResult::Continue)
+    }
+
+    // set the field to the selected candidate with the cursor at its end.
+    fn complete(&mut self, form: FORM) -> result!(FormInputResult) {
+        let candidate = self.candidates[self.selected].clone();
+
+        self.close()?;
+
+        set_field_value(self.field, 0, candidate)?;
+        form_request(form, FormRequest::EndLine)?;
+
+        Ok(FormInputResult::Continue)
+    }
+}
+
+impl Drop for FieldCompleter {
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
+
+impl fmt::Debug for FieldCompleter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FieldCompleter")
+            .field("field", &self.field)
+            .field("max_candidates", &self.max_candidates)
+            .field("candidates", &self.candidates)
+            .field("selected", &self.selected)
+            .finish()
+    }
+}
+
+#[test]
+fn field_completer_test() {
+    use crate::{
+        funcs::with_test_screen,
+        form::{FieldParameters, FieldType, field_value, free_field, free_form, new_field, new_form, post_form, set_field_type, unpost_form}
+    };
+
+    with_test_screen(|_| {
+        let field = new_field(FieldParameters::new(Size { lines: 1, columns: 12 }, Origin { y: 1, x: 1 }, 0, 0)).unwrap();
+        let cities = vec!["Berlin", "Bern", "Brussels"];
+
+        set_field_type(Some(field), &FieldType::enumeration(cities.clone(), false, false).unwrap()).unwrap();
+
+        let mut fields = vec![field];
+        let form = new_form(&mut fields).unwrap();
+        let mut completer = FieldCompleter::new(field, cities);
+        let keymap = FormKeyMap::default();
+
+        post_form(form).unwrap();
+
+        let mut input = |event: CharacterResult<char>| completer.handle_input(form, event, &keymap).unwrap();
+
+        for ch in "be".chars() {
+            input(CharacterResult::Character(ch));
+        }
+
+        // the typed prefix is not replaced by validating the enumeration.
+        assert_eq!(current_field_value(form, field).unwrap(), "be");
+
+        // the selection is kept while the candidates are unchanged.
+        input(CharacterResult::Key(KeyBinding::DownArrow));
+        input(CharacterResult::Character('r'));
+        input(CharacterResult::Key(KeyBinding::Enter));
+
+        assert_eq!(field_value(field, 0, true).unwrap(), "Bern");
+
+        drop(completer);
+        unpost_form(form).unwrap();
+        free_form(form).unwrap();
+        free_field(field).unwrap();
+    });
+}
+
+#[test]
+fn field_completer_dropdown_test() {
+    use crate::{
+        funcs::with_test_screen,
+        form::{FieldParameters, free_field, free_form, new_field, new_form, post_form, unpost_form}
+    };
+
+    with_test_screen(|_| {
+        unsafe { libc::setlocale(libc::LC_CTYPE, c"C.UTF-8".as_ptr()) };
+
+        let field = new_field(FieldParameters::new(Size { lines: 1, columns: 5 }, Origin { y: 1, x: 1 }, 0, 0)).unwrap();
+        let mut fields = vec![field];
+        let form = new_form(&mut fields).unwrap();
+        let mut completer = FieldCompleter::new(field, vec!["東京都", "東京", "Bern", "Berlin"]);
+        let keymap = FormKeyMap::default();
+
+        post_form(form).unwrap();
+
+        let mut input = |completer: &mut FieldCompleter, ch| completer.handle_input(form, CharacterResult::Character(ch), &keymap).unwrap();
+
+        // the dropdown is as wide as the widest candidate in screen columns.
+        input(&mut completer, '東');
+
+        assert_eq!(getmaxx(completer.window).unwrap(), 8);
+
+        completer.close().unwrap();
+        form_request(form, FormRequest::ClearField).unwrap();
+
+        // the dropdown is closed when the only candidate has been typed in full ignoring case.
+        for ch in "ber".chars() {
+            input(&mut completer, ch);
+        }
+
+        assert!(completer.is_open());
+
+        input(&mut completer, 'n');
+
+        assert_eq!(current_field_value(form, field).unwrap(), "bern");
+        assert!(!completer.is_open());
+
+        drop(completer);
+        unpost_form(form).unwrap();
+        free_form(form).unwrap();
+        free_field(field).unwrap();
+    });
+}
@@ -0,0 +1,83 @@
+/*
+    src/form/fieldspec.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use crate::{Size, form::FieldType};
+
+/// The specification of a field added to a form with `FormBuilder::field()`.
+#[derive(Clone, Debug)]
+pub struct FieldSpec {
+    size:      Size,
+    fieldtype: Option<FieldType>,
+    password:  Option<Option<char>>
+}
+
+impl FieldSpec {
+    /// A single line text field `width` columns wide.
+    pub fn text(width: i32) -> Self {
+        Self { size: Size { lines: 1, columns: width }, fieldtype: None, password: None }
+    }
+
+    /// A multi-line text field.
+    pub fn multi_line(lines: i32, width: i32) -> Self {
+        Self { size: Size { lines, columns: width }, fieldtype: None, password: None }
+    }
+
+    /// An integer field between `minimum` and `maximum` wide enough for either value.
+    pub fn integer(minimum: i64, maximum: i64) -> Self {
+        let width = minimum.to_string().len().max(maximum.to_string().len());
+
+        Self { size: Size { lines: 1, columns: width as i32 }, fieldtype: Some(FieldType::integer(0, minimum, maximum)), password: None }
+    }
+
+    /// A single line field `width` columns wide of a field type.
+    pub fn typed(width: i32, fieldtype: FieldType) -> Self {
+        Self { size: Size { lines: 1, columns: width }, fieldtype: Some(fieldtype), password: None }
+    }
+
+    /// A single line password field `width` columns wide, if `mask` is given then
+    /// it is displayed in place of each character (see `set_field_password()`).
+    pub fn password(width: i32, mask: Option<char>) -> Self {
+        Self { size: Size { lines: 1, columns: width }, fieldtype: None, password: Some(mask) }
+    }
+
+    /// Set the field type of the field.
+    pub fn set_field_type(mut self, fieldtype: FieldType) -> Self {
+        self.fieldtype = Some(fieldtype);
+
+        self
+    }
+
+    /// The height and width of the field.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// The field type of the field.
+    pub fn fieldtype(&self) -> Option<&FieldType> {
+        self.fieldtype.as_ref()
+    }
+
+    /// The mask of a password field, `None` if the field is not a password field.
+    pub fn password_mask(&self) -> Option<Option<char>> {
+        self.password
+    }
+}
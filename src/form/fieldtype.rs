@@ -0,0 +1,366 @@
+/*
+    src/form/fieldtype.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{ptr, rc::Rc, cell::RefCell, collections::HashMap, ffi::CString};
+use crate::{
+    Origin, Size,
+    shims::{nform, bindings, constants::E_OK},
+    form::{
+        FIELD, FIELDTYPE, FieldParameters, FieldValidator, NCurseswFormError, free_field, new_field,
+        ncurseswformerror::ncursesw_form_error_from_rc,
+        fieldchecks::{is_email, is_float, is_hex, is_ipv6, is_iso_date, is_path, is_port, is_time},
+        fieldvalidator::{
+            BuiltinArg, builtin_fieldtype, linked_fieldtype, push_pending_arg, push_pending_builtin_arg,
+            set_validator_type, validator_fieldtype
+        }
+    }
+};
+
+thread_local! {
+    // The field types set with `set_field_type()` keyed on the field handle (or
+    // the null handle for the default field type), this keeps the memory
+    // referenced by the field type alive for as long as the field uses it.
+    static FIELD_TYPES: RefCell<HashMap<usize, FieldType>> = RefCell::new(HashMap::new());
+}
+
+/// A form field type (see `set_field_type()`).
+///
+/// The field type owns any memory that NCurses needs to access (i.e. the
+/// values of an enumeration, the regular expression or the closures of a
+/// `FieldValidator`), once set on a field
+/// this memory is kept until the field's type is replaced or the field is
+/// freed with `free_field()`.
+///
+/// ```text
+/// set_field_type(Some(field), &FieldType::enumeration(vec!["Red", "Green", "Blue"], false, true)?)?;
+/// set_field_type(Some(field), &FieldType::regexp("^[0-9]+ *$")?)?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct FieldType {
+    args:    Rc<FieldTypeArgs>,
+    message: Option<String>
+}
+
+#[derive(Debug)]
+enum FieldTypeArgs {
+    AlphaNumeric(i32),
+    Alpha(i32),
+    Enum { _values: Vec<CString>, value_list: Vec<*const i8>, case_sensitive: bool, unique: bool },
+    Integer(i32, libc::c_long, libc::c_long),
+    Numeric(i32, libc::c_double, libc::c_double),
+    RegExp(CString),
+    Ipv4,
+    Validator(Rc<FieldValidator>),
+    Link(FieldType, FieldType),
+    // a built-in field type linked with `FieldType::link()`, the field type is
+    // kept for the memory its argument may reference.
+    Builtin { builtin: Rc<BuiltinArg>, _fieldtype: FieldType }
+}
+
+impl FieldType {
+    fn new(args: FieldTypeArgs) -> Self {
+        Self { args: Rc::new(args), message: None }
+    }
+
+    /// Alphanumeric data, `width` is the minimum width of the data.
+    pub fn alpha_numeric(width: i32) -> Self {
+        Self::new(FieldTypeArgs::AlphaNumeric(width))
+    }
+
+    /// Alphabetic data, `width` is the minimum width of the data.
+    pub fn alpha(width: i32) -> Self {
+        Self::new(FieldTypeArgs::Alpha(width))
+    }
+
+    /// One of a list of values. If `case_sensitive` is `true` then the values
+    /// are matched case-sensitively and if `unique` is `true` then a partial
+    /// value must uniquely match one of the values.
+    pub fn enumeration<S: Into<Vec<u8>>>(values: Vec<S>, case_sensitive: bool, unique: bool) -> form_result!(Self) {
+        let values = values.into_iter().map(CString::new).collect::<Result<Vec<CString>, _>>()?;
+
+        let mut value_list: Vec<*const i8> = values.iter().map(|value| value.as_ptr()).collect();
+        value_list.push(ptr::null());
+
+        Ok(Self::new(FieldTypeArgs::Enum { _values: values, value_list, case_sensitive, unique }))
+    }
+
+    /// An integer, `padding` is the number of digits to pad with zeros to when
+    /// displayed. If `minimum` and `maximum` are both zero then the range is not checked.
+    pub fn integer(padding: i32, minimum: i64, maximum: i64) -> Self {
+        Self::new(FieldTypeArgs::Integer(padding, minimum as libc::c_long, maximum as libc::c_long))
+    }
+
+    /// A decimal number, `padding` is the number of digits of precision when
+    /// displayed. If `minimum` and `maximum` are both zero then the range is not checked.
+    pub fn numeric(padding: i32, minimum: f64, maximum: f64) -> Self {
+        Self::new(FieldTypeArgs::Numeric(padding, minimum, maximum))
+    }
+
+    /// Data matching a POSIX regular expression. Note that the whole field is
+    /// matched including any trailing blanks.
+    pub fn regexp<S: Into<Vec<u8>>>(regexp: S) -> form_result!(Self) {
+        Ok(Self::new(FieldTypeArgs::RegExp(CString::new(regexp)?)))
+    }
+
+    /// An IPv4 address.
+    pub fn ipv4() -> Self {
+        Self::new(FieldTypeArgs::Ipv4)
+    }
+
+    /// A custom field type implemented by Rust closures.
+    pub fn validator(validator: FieldValidator) -> Self {
+        Self::new(FieldTypeArgs::Validator(Rc::new(validator)))
+    }
+
+    /// An ISO 8601 calendar date (YYYY-MM-DD).
+    pub fn iso_date() -> Self {
+        Self::validator(FieldValidator::new(is_iso_date).set_char_check(|ch| ch.is_ascii_digit() || ch == '-'))
+    }
+
+    /// A 24 hour time (HH:MM or HH:MM:SS).
+    pub fn time() -> Self {
+        Self::validator(FieldValidator::new(is_time).set_char_check(|ch| ch.is_ascii_digit() || ch == ':'))
+    }
+
+    /// An e-mail address, the domain must have at least two labels (i.e. "example.com").
+    pub fn email() -> Self {
+        Self::validator(FieldValidator::new(is_email).set_char_check(|ch| !ch.is_whitespace() && !ch.is_control()))
+    }
+
+    /// A hexadecimal number with an optional "0x" prefix.
+    pub fn hex() -> Self {
+        Self::validator(FieldValidator::new(is_hex).set_char_check(|ch| ch.is_ascii_hexdigit() || ch == 'x' || ch == 'X'))
+    }
+
+    /// An IPv6 address.
+    pub fn ipv6() -> Self {
+        Self::validator(FieldValidator::new(is_ipv6).set_char_check(|ch| ch.is_ascii_hexdigit() || ch == ':' || ch == '.'))
+    }
+
+    /// A TCP/UDP port number (1 to 65535).
+    pub fn port() -> Self {
+        Self::validator(FieldValidator::new(is_port).set_char_check(|ch| ch.is_ascii_digit()))
+    }
+
+    /// A decimal number with an optional sign and at most `precision` decimal places.
+    pub fn float(precision: usize) -> Self {
+        Self::validator(
+            FieldValidator::new(move |value| is_float(value, precision))
+                .set_char_check(move |ch| ch.is_ascii_digit() || ch == '+' || ch == '-' || (ch == '.' && precision > 0))
+        )
+    }
+
+    /// A file path, if `must_exist` is `true` then the path must exist when the field is validated.
+    pub fn path(must_exist: bool) -> Self {
+        Self::validator(FieldValidator::new(move |value| is_path(value, must_exist)).set_char_check(|ch| !ch.is_control()))
+    }
+
+    /// Link two field types using `link_fieldtype()`, a field is valid if it is
+    /// valid for either field type (i.e. `FieldType::link(&FieldType::ipv6(), &FieldType::hex())`).
+    ///
+    /// Any field types can be linked, including the built-in field types (i.e.
+    /// `FieldType::link(&FieldType::integer(0, 1, 100), &FieldType::enumeration(vec!["none"], false, false)?)`)
+    /// and links of field types.
+    pub fn link(left: &FieldType, right: &FieldType) -> form_result!(Self) {
+        Ok(Self::new(FieldTypeArgs::Link(left.linkable()?, right.linkable()?)))
+    }
+
+    /// Set the message displayed when a field of this type is invalid (see `FormFeedback`).
+    pub fn set_message<S: Into<String>>(mut self, message: S) -> Self {
+        self.message = Some(message.into());
+
+        self
+    }
+
+    /// The message displayed when a field of this type is invalid, if no
+    /// message has been set then the message of a `FieldValidator` is used.
+    pub fn message(&self) -> Option<&str> {
+        match &*self.args {
+            FieldTypeArgs::Validator(validator) if self.message.is_none() => validator.message(),
+            _                                                             => self.message.as_deref()
+        }
+    }
+
+    // the field type is set through the crate's own NCurses field types (see
+    // `validator_fieldtype()`) rather than being a built-in field type.
+    fn is_custom(&self) -> bool {
+        matches!(*self.args, FieldTypeArgs::Validator(_) | FieldTypeArgs::Link(..) | FieldTypeArgs::Builtin { .. })
+    }
+
+    // the field type as one side of a link, the argument of a built-in field
+    // type is created now so that it can be passed to `make_builtin_arg()`.
+    fn linkable(&self) -> form_result!(Self) {
+        if self.is_custom() {
+            return Ok(self.clone());
+        }
+
+        let field = new_field(FieldParameters::new(Size { lines: 1, columns: 1 }, Origin { y: 0, x: 0 }, 0, 0))?;
+
+        let builtin = match self.set(Some(field)) {
+            Ok(E_OK)    => BuiltinArg::from_field(field),
+            Ok(rc)      => Err(form_function_error_with_rc!("set_field_type", rc)),
+            Err(source) => Err(source)
+        };
+
+        free_field(field)?;
+
+        Ok(Self { args: Rc::new(FieldTypeArgs::Builtin { builtin: Rc::new(builtin?), _fieldtype: self.clone() }), message: self.message.clone() })
+    }
+
+    // the NCurses field type of a validator, a linked built-in field type or a link of them.
+    fn validator_fieldtype(&self) -> form_result!(FIELDTYPE) {
+        match &*self.args {
+            FieldTypeArgs::Link(left, right) => linked_fieldtype(left.validator_fieldtype()?, right.validator_fieldtype()?),
+            FieldTypeArgs::Builtin { .. }    => builtin_fieldtype(),
+            _                                => validator_fieldtype()
+        }
+    }
+
+    // queue the validators in the order that NCurses creates their arguments
+    // (depth first, left to right).
+    fn push_validators(&self) {
+        match &*self.args {
+            FieldTypeArgs::Validator(validator)    => push_pending_arg(validator),
+            FieldTypeArgs::Builtin { builtin, .. } => push_pending_builtin_arg(builtin),
+            FieldTypeArgs::Link(left, right)       => {
+                left.push_validators();
+                right.push_validators();
+            },
+            _                                      => { }
+        }
+    }
+
+    // set the field type of a field returning the NCurses return code.
+    pub(in crate::form) fn set(&self, field: Option<FIELD>) -> form_result!(i32) {
+        let fieldtype = match &*self.args {
+            FieldTypeArgs::Validator(_)                                    |
+            FieldTypeArgs::Link(..)                                        |
+            FieldTypeArgs::Builtin { .. }                                  => {
+                let fieldtype = self.validator_fieldtype()?;
+
+                self.push_validators();
+
+                return Ok(set_validator_type(field, fieldtype));
+            },
+            FieldTypeArgs::AlphaNumeric(width)                             => nform::FieldType::AlphaNumeric(*width),
+            FieldTypeArgs::Alpha(width)                                    => nform::FieldType::Alpha(*width),
+            FieldTypeArgs::Enum { value_list, case_sensitive, unique, .. } => nform::FieldType::Enum(value_list.as_ptr(), *case_sensitive, *unique),
+            FieldTypeArgs::Integer(padding, minimum, maximum)              => nform::FieldType::Integer(*padding, *minimum, *maximum),
+            FieldTypeArgs::Numeric(padding, minimum, maximum)              => nform::FieldType::Numeric(*padding, *minimum, *maximum),
+            FieldTypeArgs::RegExp(regexp)                                  => nform::FieldType::RegExp(regexp.as_ptr()),
+            FieldTypeArgs::Ipv4                                            => nform::FieldType::Ipv4
+        };
+
+        Ok(unsafe { nform::set_field_type(field, fieldtype) })
+    }
+}
+
+/// Returns the message of the field type set on a field with `set_field_type()`
+/// (see `FieldType::set_message()`).
+pub fn field_type_message(field: FIELD) -> Option<String> {
+    FIELD_TYPES.with(|fieldtypes| {
+        fieldtypes.borrow().get(&(field as usize)).and_then(|fieldtype| fieldtype.message().map(str::to_string))
+    })
+}
+
+// keep the field type alive while the field uses it, this is called by
+// `set_field_type()` once NCurses has accepted the field type.
+pub(in crate::form) fn retain_field_type(field: Option<FIELD>, fieldtype: &FieldType) {
+    let key = field.map_or(0, |field| field as usize);
+
+    FIELD_TYPES.with(|fieldtypes| fieldtypes.borrow_mut().insert(key, fieldtype.clone()));
+}
+
+// release the field type of a field, this is called by `free_field()`
+// as NCurses may reuse the field handle for a subsequent field.
+pub(in crate::form) fn release_field_type(field: FIELD) {
+    FIELD_TYPES.with(|fieldtypes| fieldtypes.borrow_mut().remove(&(field as usize)));
+}
+
+// remove the NCurses field type of a field so that NCurses no longer validates
+// it, the field type is kept and is set again by `resume_field_type()`. Returns
+// false if the field has no field type set with `set_field_type()`.
+pub(in crate::form) fn suspend_field_type(field: FIELD) -> form_result!(bool) {
+    if !FIELD_TYPES.with(|fieldtypes| fieldtypes.borrow().contains_key(&(field as usize))) {
+        return Ok(false);
+    }
+
+    match unsafe { bindings::set_field_type(field, ptr::null_mut()) } {
+        E_OK => Ok(true),
+        rc   => Err(form_function_error_with_rc!("set_field_type", rc))
+    }
+}
+
+// set the field type of a field suspended by `suspend_field_type()` again.
+pub(in crate::form) fn resume_field_type(field: FIELD) -> form_result!(()) {
+    let fieldtype = FIELD_TYPES.with(|fieldtypes| fieldtypes.borrow().get(&(field as usize)).cloned());
+
+    match fieldtype.map_or(Ok(E_OK), |fieldtype| fieldtype.set(Some(field)))? {
+        E_OK => Ok(()),
+        rc   => Err(form_function_error_with_rc!("set_field_type", rc))
+    }
+}
+
+#[test]
+fn field_type_link_test() {
+    use crate::{
+        funcs::with_test_screen,
+        form::{
+            FieldOptions, FormInputResult, FormRequest, field_opts_off, field_value, forminput::form_request,
+            free_form, new_form, post_form, set_field_type, set_field_value, unpost_form
+        }
+    };
+
+    with_test_screen(|_| {
+        let field = new_field(FieldParameters::new(Size { lines: 1, columns: 10 }, Origin { y: 0, x: 0 }, 0, 0)).unwrap();
+        let none = FieldType::enumeration(vec!["none"], false, false).unwrap();
+
+        // the arguments of the built-in field types are kept by the field.
+        set_field_type(Some(field), &FieldType::link(&FieldType::integer(0, 1, 100), &FieldType::link(&none, &FieldType::ipv4()).unwrap()).unwrap()).unwrap();
+        field_opts_off(Some(field), FieldOptions::default().set_pass_ok(true)).unwrap();
+        drop(none);
+
+        let mut fields = vec![field];
+        let form = new_form(&mut fields).unwrap();
+
+        post_form(form).unwrap();
+
+        let is_valid = |value: &str| {
+            set_field_value(field, 0, value).unwrap();
+
+            matches!(form_request(form, FormRequest::Validate).unwrap(), FormInputResult::Continue)
+        };
+
+        assert!(is_valid("42"));
+        assert!(is_valid("10.0.0.1"));
+        assert!(!is_valid("420"));
+        assert!(!is_valid("abc"));
+
+        // the enumeration completes the value.
+        assert!(is_valid("no"));
+        assert_eq!(field_value(field, 0, true).unwrap(), "none");
+
+        unpost_form(form).unwrap();
+        free_form(form).unwrap();
+        free_field(field).unwrap();
+    });
+}
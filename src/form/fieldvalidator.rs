@@ -0,0 +1,347 @@
+/*
+    src/form/fieldvalidator.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{
+    ptr, fmt, rc::Rc, cell::{Cell, RefCell}, collections::{HashMap, VecDeque}
+};
+use crate::{
+    shims::{bindings, bindings::va_list},
+    form::{
+        FIELD, FIELDTYPE, NCurseswFormError, field_value, link_fieldtype,
+        new_fieldtype, set_field_value, set_fieldtype_arg, set_fieldtype_choice
+    }
+};
+
+type FieldCheckFn = Box<dyn Fn(&str) -> bool>;
+type CharCheckFn = Box<dyn Fn(char) -> bool>;
+type ChoiceFn = Box<dyn Fn(&str) -> Option<String>>;
+
+thread_local! {
+    // The NCurses field type used for all validators, created on first use.
+    static VALIDATOR_FIELDTYPE: Cell<FIELDTYPE> = const { Cell::new(ptr::null_mut()) };
+    // The NCurses field types linking validators (see `FieldType::link()`) keyed
+    // on the two field types being linked.
+    static LINKED_FIELDTYPES: RefCell<HashMap<(usize, usize), FIELDTYPE>> = RefCell::new(HashMap::new());
+    // Validators waiting to be picked up by `make_arg()` during `set_field_type()`.
+    static PENDING_ARGS: RefCell<VecDeque<*const FieldValidator>> = const { RefCell::new(VecDeque::new()) };
+    // The NCurses field type calling the built-in field types linked with
+    // `FieldType::link()`, created on first use.
+    static BUILTIN_FIELDTYPE: Cell<FIELDTYPE> = const { Cell::new(ptr::null_mut()) };
+    // Built-in field types waiting to be picked up by `make_builtin_arg()` during `set_field_type()`.
+    static PENDING_BUILTIN_ARGS: RefCell<VecDeque<*const BuiltinArg>> = const { RefCell::new(VecDeque::new()) };
+}
+
+/// A custom field type implemented by Rust closures (see `FieldType::validator()`).
+///
+/// The field closure is passed the contents of the field (with the trailing
+/// padding removed, see `field_value()`) when the field is validated and the
+/// optional character closure is passed each character as it is entered.
+///
+/// The optional choice closures implement `FormRequest::NextChoice` and
+/// `FormRequest::PreviousChoice`, they are passed the contents of the field
+/// and return the value the field should be set to (or `None` if there is no
+/// next or previous choice).
+///
+/// ```text
+/// let even = FieldValidator::new(|value| value.parse::<u32>().map_or(false, |n| n % 2 == 0))
+///     .set_char_check(|ch| ch.is_ascii_digit());
+///
+/// set_field_type(Some(field), &FieldType::validator(even))?;
+/// ```
+pub struct FieldValidator {
+    field_check: FieldCheckFn,
+    char_check:  Option<CharCheckFn>,
+    next_choice: Option<ChoiceFn>,
+    prev_choice: Option<ChoiceFn>,
+    message:     Option<String>
+}
+
+impl FieldValidator {
+    /// Create a validator that checks the contents of a field.
+    pub fn new<F: Fn(&str) -> bool + 'static>(field_check: F) -> Self {
+        Self { field_check: Box::new(field_check), char_check: None, next_choice: None, prev_choice: None, message: None }
+    }
+
+    /// Set the closure that checks each character as it is entered.
+    pub fn set_char_check<F: Fn(char) -> bool + 'static>(mut self, char_check: F) -> Self {
+        self.char_check = Some(Box::new(char_check));
+
+        self
+    }
+
+    /// Set the closures that return the next and previous choice of a field's value.
+    pub fn set_choices<N, P>(mut self, next_choice: N, prev_choice: P) -> Self
+        where N: Fn(&str) -> Option<String> + 'static,
+              P: Fn(&str) -> Option<String> + 'static
+    {
+        self.next_choice = Some(Box::new(next_choice));
+        self.prev_choice = Some(Box::new(prev_choice));
+
+        self
+    }
+
+    /// Set the message displayed when a field is invalid (see `FormFeedback`).
+    pub fn set_message<S: Into<String>>(mut self, message: S) -> Self {
+        self.message = Some(message.into());
+
+        self
+    }
+
+    /// The message displayed when a field is invalid.
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    /// Check the contents of a field.
+    pub fn check_field(&self, value: &str) -> bool {
+        (self.field_check)(value)
+    }
+
+    /// Check a character entered into a field.
+    pub fn check_char(&self, ch: char) -> bool {
+        self.char_check.as_ref().is_none_or(|char_check| char_check(ch))
+    }
+}
+
+impl fmt::Debug for FieldValidator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FieldValidator")
+            .field("char_check", &self.char_check.is_some())
+            .field("choices", &self.next_choice.is_some())
+            .field("message", &self.message)
+            .finish()
+    }
+}
+
+// A built-in NCurses field type (i.e. `TYPE_INTEGER`) and a copy of its
+// argument so that it can be linked with `FieldType::link()`. NCurses creates
+// the arguments of a linked field type from the variable arguments passed to
+// `set_field_type()` which can't be built at runtime, so a linked built-in
+// field type is set as `BUILTIN_FIELDTYPE` which calls the built-in field
+// type with the argument passed through `PENDING_BUILTIN_ARGS`.
+#[derive(Debug)]
+pub(in crate::form) struct BuiltinArg {
+    fieldtype: FIELDTYPE,
+    arg:       *mut libc::c_void
+}
+
+impl BuiltinArg {
+    // copy the field type and argument of a field.
+    pub(in crate::form) fn from_field(field: FIELD) -> form_result!(Self) {
+        let (fieldtype, arg) = unsafe { ((*field).type_, (*field).arg) };
+
+        if fieldtype.is_null() {
+            return Err(NCurseswFormError::BadArgument { func: "FieldType::link".to_string() });
+        }
+
+        let arg = match unsafe { (*fieldtype).copyarg } {
+            Some(copy_arg) if !arg.is_null() => unsafe { copy_arg(arg) },
+            _                                => arg
+        };
+
+        Ok(Self { fieldtype, arg })
+    }
+}
+
+impl Drop for BuiltinArg {
+    fn drop(&mut self) {
+        if let Some(free_arg) = unsafe { (*self.fieldtype).freearg } {
+            if !self.arg.is_null() {
+                unsafe { free_arg(self.arg) }
+            }
+        }
+    }
+}
+
+// Set the field type of a field to a validator (or a link of validators and
+// built-in field types)
+// returning the NCurses return code, the validators must have been queued
+// with `push_pending_arg()` (or `push_pending_builtin_arg()`) in the order NCurses creates their arguments.
+pub(in crate::form) fn set_validator_type(field: Option<FIELD>, fieldtype: FIELDTYPE) -> i32 {
+    // the validators are passed to `make_arg()` through `PENDING_ARGS` rather
+    // than as variable arguments.
+    let rc = unsafe { bindings::set_field_type(field.unwrap_or(ptr::null_mut()), fieldtype) };
+
+    release_pending_args();
+
+    rc
+}
+
+// the NCurses field type for validators.
+pub(in crate::form) fn validator_fieldtype() -> form_result!(FIELDTYPE) {
+    let fieldtype = VALIDATOR_FIELDTYPE.with(Cell::get);
+
+    if !fieldtype.is_null() {
+        return Ok(fieldtype);
+    }
+
+    let fieldtype = new_fieldtype(field_check, char_check)?;
+
+    set_fieldtype_arg(fieldtype, make_arg, Some(copy_arg), Some(free_arg))?;
+    set_fieldtype_choice(fieldtype, next_choice, prev_choice)?;
+
+    VALIDATOR_FIELDTYPE.with(|cell| cell.set(fieldtype));
+
+    Ok(fieldtype)
+}
+
+// the NCurses field type linking two validator field types.
+pub(in crate::form) fn linked_fieldtype(left: FIELDTYPE, right: FIELDTYPE) -> form_result!(FIELDTYPE) {
+    let key = (left as usize, right as usize);
+
+    if let Some(fieldtype) = LINKED_FIELDTYPES.with(|fieldtypes| fieldtypes.borrow().get(&key).copied()) {
+        return Ok(fieldtype);
+    }
+
+    let fieldtype = link_fieldtype(left, right)?;
+
+    LINKED_FIELDTYPES.with(|fieldtypes| fieldtypes.borrow_mut().insert(key, fieldtype));
+
+    Ok(fieldtype)
+}
+
+// the NCurses field type for linked built-in field types.
+pub(in crate::form) fn builtin_fieldtype() -> form_result!(FIELDTYPE) {
+    let fieldtype = BUILTIN_FIELDTYPE.with(Cell::get);
+
+    if !fieldtype.is_null() {
+        return Ok(fieldtype);
+    }
+
+    let fieldtype = new_fieldtype(builtin_field_check, builtin_char_check)?;
+
+    set_fieldtype_arg(fieldtype, make_builtin_arg, Some(copy_builtin_arg), Some(free_builtin_arg))?;
+    set_fieldtype_choice(fieldtype, builtin_next_choice, builtin_prev_choice)?;
+
+    BUILTIN_FIELDTYPE.with(|cell| cell.set(fieldtype));
+
+    Ok(fieldtype)
+}
+
+// release any validators and built-in field types not picked up by
+// `make_arg()` or `make_builtin_arg()` (i.e. when NCurses has rejected
+// the field type).
+fn release_pending_args() {
+    while let Some(arg) = PENDING_ARGS.with(|args| args.borrow_mut().pop_front()) {
+        unsafe { drop(Rc::from_raw(arg)) }
+    }
+
+    while let Some(arg) = PENDING_BUILTIN_ARGS.with(|args| args.borrow_mut().pop_front()) {
+        unsafe { drop(Rc::from_raw(arg)) }
+    }
+}
+
+// queue a validator to be picked up by `make_arg()`.
+pub(in crate::form) fn push_pending_arg(validator: &Rc<FieldValidator>) {
+    PENDING_ARGS.with(|args| args.borrow_mut().push_back(Rc::into_raw(Rc::clone(validator))));
+}
+
+// queue a built-in field type to be picked up by `make_builtin_arg()`.
+pub(in crate::form) fn push_pending_builtin_arg(builtin: &Rc<BuiltinArg>) {
+    PENDING_BUILTIN_ARGS.with(|args| args.borrow_mut().push_back(Rc::into_raw(Rc::clone(builtin))));
+}
+
+unsafe fn validator<'a>(arg: *const libc::c_void) -> Option<&'a FieldValidator> {
+    (arg as *const FieldValidator).as_ref()
+}
+
+extern "C" fn make_arg(_: *mut va_list) -> *mut libc::c_void {
+    PENDING_ARGS.with(|args| args.borrow_mut().pop_front()).map_or(ptr::null_mut(), |arg| arg as *mut libc::c_void)
+}
+
+unsafe extern "C" fn copy_arg(arg: *const libc::c_void) -> *mut libc::c_void {
+    if !arg.is_null() {
+        Rc::increment_strong_count(arg as *const FieldValidator);
+    }
+
+    arg as *mut libc::c_void
+}
+
+unsafe extern "C" fn free_arg(arg: *mut libc::c_void) {
+    if !arg.is_null() {
+        drop(Rc::from_raw(arg as *const FieldValidator));
+    }
+}
+
+unsafe extern "C" fn field_check(field: FIELD, arg: *const libc::c_void) -> bool {
+    validator(arg).is_none_or(|validator| {
+        field_value(field, 0, true).is_ok_and(|value| validator.check_field(&value))
+    })
+}
+
+unsafe extern "C" fn char_check(ch: i32, arg: *const libc::c_void) -> bool {
+    validator(arg).is_none_or(|validator| {
+        char::from_u32(ch as u32).is_some_and(|ch| validator.check_char(ch))
+    })
+}
+
+unsafe extern "C" fn next_choice(field: FIELD, arg: *const libc::c_void) -> bool {
+    validator(arg).is_some_and(|validator| choice(field, validator.next_choice.as_ref()))
+}
+
+unsafe extern "C" fn prev_choice(field: FIELD, arg: *const libc::c_void) -> bool {
+    validator(arg).is_some_and(|validator| choice(field, validator.prev_choice.as_ref()))
+}
+
+fn choice(field: FIELD, choice: Option<&ChoiceFn>) -> bool {
+    choice
+        .and_then(|choice| field_value(field, 0, true).ok().and_then(|value| choice(&value)))
+        .is_some_and(|value| set_field_value(field, 0, value).is_ok())
+}
+
+unsafe fn builtin<'a>(arg: *const libc::c_void) -> Option<&'a BuiltinArg> {
+    (arg as *const BuiltinArg).as_ref()
+}
+
+extern "C" fn make_builtin_arg(_: *mut va_list) -> *mut libc::c_void {
+    PENDING_BUILTIN_ARGS.with(|args| args.borrow_mut().pop_front()).map_or(ptr::null_mut(), |arg| arg as *mut libc::c_void)
+}
+
+unsafe extern "C" fn copy_builtin_arg(arg: *const libc::c_void) -> *mut libc::c_void {
+    if !arg.is_null() {
+        Rc::increment_strong_count(arg as *const BuiltinArg);
+    }
+
+    arg as *mut libc::c_void
+}
+
+unsafe extern "C" fn free_builtin_arg(arg: *mut libc::c_void) {
+    if !arg.is_null() {
+        drop(Rc::from_raw(arg as *const BuiltinArg));
+    }
+}
+
+unsafe extern "C" fn builtin_field_check(field: FIELD, arg: *const libc::c_void) -> bool {
+    builtin(arg).is_none_or(|builtin| (*builtin.fieldtype).fieldcheck.ofcheck.is_none_or(|check| check(field, builtin.arg)))
+}
+
+unsafe extern "C" fn builtin_char_check(ch: i32, arg: *const libc::c_void) -> bool {
+    builtin(arg).is_none_or(|builtin| (*builtin.fieldtype).charcheck.occheck.is_none_or(|check| check(ch, builtin.arg)))
+}
+
+unsafe extern "C" fn builtin_next_choice(field: FIELD, arg: *const libc::c_void) -> bool {
+    builtin(arg).is_some_and(|builtin| (*builtin.fieldtype).enum_next.onext.is_some_and(|choice| choice(field, builtin.arg)))
+}
+
+unsafe extern "C" fn builtin_prev_choice(field: FIELD, arg: *const libc::c_void) -> bool {
+    builtin(arg).is_some_and(|builtin| (*builtin.fieldtype).enum_prev.oprev.is_some_and(|choice| choice(field, builtin.arg)))
+}
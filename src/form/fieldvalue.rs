@@ -0,0 +1,180 @@
+/*
+    src/form/fieldvalue.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{ffi::CString, convert::TryFrom};
+use crate::{
+    NCurseswError, Origin, WideString, getmaxyx, mvwinnwstr,
+    funcs::char_width,
+    form::{FIELD, FORM, NCurseswFormError, dynamic_field_info, field_buffer, field_pad, set_field_buffer}
+};
+
+/// Returns the contents of the given numbered buffer of a field as a string.
+///
+/// The buffer is decoded from the multibyte (UTF-8) representation returned
+/// by `field_buffer()`, any invalid sequences are replaced with `U+FFFD`.
+///
+/// If `trim` is `true` then the trailing blanks padding each row of the field
+/// are removed, for a multi-line field each row becomes a line terminated by
+/// `'\n'` (except the last) and trailing empty rows are removed. The current
+/// size of a dynamic field is used to split the buffer into rows.
+pub fn field_value(field: FIELD, buffer_number: i32, trim: bool) -> form_result!(String) {
+    let buffer: Vec<u8> = field_buffer(field, buffer_number)?.into_iter().map(|byte| byte as u8).collect();
+    let value = String::from_utf8_lossy(&buffer);
+
+    if trim {
+        let columns = usize::try_from(dynamic_field_info(field)?.size().columns)?;
+
+        Ok(split_rows(&value, columns))
+    } else {
+        Ok(value.into_owned())
+    }
+}
+
+/// Returns the contents of the given numbered buffer of a field as a wide string
+/// (see `field_value()`).
+pub fn field_wide_value(field: FIELD, buffer_number: i32, trim: bool) -> form_result!(WideString) {
+    Ok(WideString::from(field_value(field, buffer_number, trim)?))
+}
+
+/// Sets the given numbered buffer of a field to a string.
+///
+/// For a multi-line field each line of `value` (separated by `'\n'`) is
+/// padded with blanks to the width of the field so that each line starts
+/// on a new row of the field.
+pub fn set_field_value<S: Into<String>>(field: FIELD, buffer_number: i32, value: S) -> form_result!(()) {
+    let value = value.into();
+    let size = dynamic_field_info(field)?.size();
+
+    let value = if size.lines > 1 && value.contains('\n') {
+        join_rows(&value, usize::try_from(size.columns)?)
+    } else {
+        value
+    };
+
+    let buffer: Vec<i8> = CString::new(value)?.into_bytes_with_nul().into_iter().map(|byte| byte as i8).collect();
+
+    set_field_buffer(field, buffer_number, &buffer)
+}
+
+// the contents of the current field of a posted form as edited (with the
+// trailing padding removed, see `field_value()`), NCurses only updates the
+// buffer of the current field when it is validated and validating it may
+// change the contents (i.e. a `FieldType::enumeration()` field).
+pub(in crate::form) fn current_field_value(form: FORM, field: FIELD) -> result!(String) {
+    // `w` is the window the current field is edited in.
+    let window = unsafe { (*form).w };
+    let size = getmaxyx(window)?;
+    let pad = field_pad(Some(field))?;
+
+    let rows = (0..size.lines).map(|y| {
+        let row: String = mvwinnwstr(window, Origin { y, x: 0 }, size.columns)?.into();
+
+        Ok(row.replace(pad, " "))
+    }).collect::<result!(Vec<String>)>()?;
+
+    Ok(trim_rows(rows))
+}
+
+// split a field buffer into rows of `columns` screen columns removing the
+// trailing blanks of each row and any trailing empty rows.
+fn split_rows(value: &str, columns: usize) -> String {
+    trim_rows(wrap_rows(value, columns).into_iter().map(|(row, _)| row).collect())
+}
+
+// wrap `value` into rows of `columns` screen columns returning each row and
+// its width, a double-width character that does not fit at the end of a
+// row starts the next row (as NCurses does).
+fn wrap_rows(value: &str, columns: usize) -> Vec<(String, usize)> {
+    let columns = columns.max(1);
+    let mut rows = vec![];
+    let (mut row, mut width) = (String::new(), 0);
+
+    for ch in value.chars() {
+        let ch_width = char_width(ch);
+
+        if width + ch_width > columns && width > 0 {
+            rows.push((std::mem::take(&mut row), width));
+            width = 0;
+        }
+
+        row.push(ch);
+        width += ch_width;
+    }
+
+    rows.push((row, width));
+
+    rows
+}
+
+// remove the trailing blanks of each row and any trailing empty rows.
+fn trim_rows(rows: Vec<String>) -> String {
+    let mut rows: Vec<String> = rows.into_iter().map(|row| row.trim_end().to_string()).collect();
+
+    while matches!(rows.last(), Some(row) if row.is_empty()) {
+        rows.pop();
+    }
+
+    rows.join("\n")
+}
+
+// pad each line of `value` to `columns` screen columns so that each line
+// starts on a new row of a field.
+fn join_rows(value: &str, columns: usize) -> String {
+    let mut lines = value.split('\n').peekable();
+    let mut rows = String::new();
+
+    while let Some(line) = lines.next() {
+        rows.push_str(line);
+
+        if lines.peek().is_some() {
+            let width = wrap_rows(line, columns).last().map_or(0, |(_, width)| *width);
+            let padding = if line.is_empty() { columns } else { columns.saturating_sub(width) };
+
+            rows.push_str(&" ".repeat(padding));
+        }
+    }
+
+    rows
+}
+
+#[test]
+fn field_value_test() {
+    use crate::funcs::with_test_screen;
+
+    assert_eq!(split_rows("héllo     wörld     ", 10), "héllo\nwörld");
+    assert_eq!(split_rows("abc       ", 10), "abc");
+    assert_eq!(split_rows("  abc     ", 10), "  abc");
+    assert_eq!(split_rows("abc                 ", 10), "abc");
+    assert_eq!(join_rows("héllo\nwörld", 10), "héllo     wörld");
+    assert_eq!(join_rows("\nabc", 5), "     abc");
+    assert_eq!(join_rows("abcde\nf", 5), "abcdef");
+
+    with_test_screen(|_| {
+        // double-width characters occupy two columns of a row.
+        unsafe { libc::setlocale(libc::LC_CTYPE, c"C.UTF-8".as_ptr()) };
+
+        assert_eq!(split_rows("日本語    東京      ", 10), "日本語\n東京");
+        assert_eq!(split_rows("abcdefghi日本 ", 10), "abcdefghi\n日本");
+        assert_eq!(join_rows("日本\n東京", 10), "日本      東京");
+        assert_eq!(join_rows("日本語日本\nx", 10), "日本語日本x");
+    });
+}
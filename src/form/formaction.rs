@@ -0,0 +1,41 @@
+/*
+    src/form/formaction.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use crate::form::FormRequest;
+
+/// The action a key or character is bound to in a `FormKeyMap`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FormAction {
+    /// Pass the request to `form_driver()`.
+    Request(FormRequest),
+    /// Toggle between insert and overlay mode.
+    ToggleMode,
+    /// Toggle revealing the password of the current field (see `set_field_password()`).
+    ToggleReveal,
+    /// Validate the current field and submit the form.
+    Submit,
+    /// Start a new line in a multi-line field (see `FormRequest::Newline`),
+    /// otherwise validate the current field and submit the form.
+    NewLineOrSubmit,
+    /// Cancel the form.
+    Cancel
+}
@@ -0,0 +1,212 @@
+/*
+    src/form/formbuilder.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::convert::TryFrom;
+use crate::{
+    normal,
+    ChtypeChar, LINES, NCurseswError, Origin, Size, delwin, derwin, newwin, r#box,
+    funcs::str_width,
+    form::{
+        Field, FieldOptions, FieldParameters, FieldSpec, Form, NCurseswFormError,
+        field_opts_off, scale_form, set_field_back, set_field_password, set_new_page
+    }
+};
+
+/// A builder of a `Form` that lays out labelled fields.
+///
+/// Each field is placed on the rows following the previous field with its label
+/// to the left, the labels are aligned in one column (as wide as the widest
+/// label in screen columns) and the fields in another.
+/// When the fields do not fit in the height available a new page is started
+/// (see `set_new_page()`). The labels are inactive fields of the form (without
+/// a name or user data) so that NCurses redraws them when the page changes,
+/// each field is named with its label (see `Field::set_name()`).
+///
+/// The window of the form is sized using `scale_form()` with a border around
+/// the subwindow, both are owned by the form.
+///
+/// ```text
+/// let form = FormBuilder::new()
+///     .field("Name", FieldSpec::text(30))
+///     .field("Port", FieldSpec::integer(1, 65535))
+///     .set_origin(Origin { y: 2, x: 4 })
+///     .build()?;
+///
+/// form.post()?;
+/// ```
+#[derive(Debug)]
+pub struct FormBuilder<T = ()> {
+    entries:   Vec<FormBuilderEntry<T>>,
+    origin:    Origin,
+    max_lines: Option<i32>
+}
+
+#[derive(Debug)]
+struct FormBuilderEntry<T> {
+    label: String,
+    spec:  FieldSpec,
+    data:  Option<T>
+}
+
+impl<T> FormBuilder<T> {
+    /// Create a new builder with no fields.
+    pub fn new() -> Self {
+        Self { entries: vec![], origin: Origin::default(), max_lines: None }
+    }
+
+    /// Add a labelled field.
+    pub fn field<S: Into<String>>(mut self, label: S, spec: FieldSpec) -> Self {
+        self.entries.push(FormBuilderEntry { label: label.into(), spec, data: None });
+
+        self
+    }
+
+    /// Add a labelled field with user data (see `Field::data()`).
+    pub fn field_with_data<S: Into<String>>(mut self, label: S, spec: FieldSpec, data: T) -> Self {
+        self.entries.push(FormBuilderEntry { label: label.into(), spec, data: Some(data) });
+
+        self
+    }
+
+    /// Set the origin of the form's window on the screen, the default is the top left.
+    pub fn set_origin(mut self, origin: Origin) -> Self {
+        self.origin = origin;
+
+        self
+    }
+
+    /// Set the maximum height of the form's window (including the border),
+    /// the default is the height of the screen below the origin.
+    pub fn set_max_lines(mut self, max_lines: i32) -> Self {
+        self.max_lines = Some(max_lines);
+
+        self
+    }
+
+    /// Build the form and its window.
+    pub fn build(self) -> result!(Form<T>) {
+        let page_lines = self.max_lines.unwrap_or_else(|| LINES() - self.origin.y) - 2;
+
+        if page_lines < 1 || self.entries.iter().any(|entry| entry.spec.size().lines > page_lines) {
+            return Err(NCurseswError::from(NCurseswFormError::BadArgument { func: "FormBuilder::build".to_string() }));
+        }
+
+        let label_width = self.entries.iter().map(|entry| str_width(&entry.label)).max().unwrap_or(0).max(1);
+        let label_width = i32::try_from(label_width)?;
+
+        let mut fields = Vec::with_capacity(self.entries.len() * 2);
+        let mut y = 0;
+
+        // any fields created before an error are freed when `fields` is dropped.
+        for entry in self.entries {
+            let size = entry.spec.size();
+            let new_page = y > 0 && y + size.lines > page_lines;
+
+            if new_page {
+                y = 0;
+            }
+
+            let label = Field::new(FieldParameters::new(Size { lines: 1, columns: label_width }, Origin { y, x: 0 }, 0, 0))?;
+
+            field_opts_off(Some(label.handle()), FieldOptions::default().set_active(true))?;
+            set_new_page(Some(label.handle()), new_page)?;
+            label.set_value(entry.label.as_str())?;
+
+            let parameters = FieldParameters::new(size, Origin { y, x: label_width + 1 }, 0, 0);
+            let field = match entry.data {
+                Some(data) => Field::with_data(parameters, data)?,
+                None       => Field::new(parameters)?
+            }.set_name(entry.label);
+
+            set_field_back(Some(field.handle()), normal::Attributes::default() | normal::Attribute::Underline)?;
+
+            if let Some(fieldtype) = entry.spec.fieldtype() {
+                field.set_field_type(fieldtype)?;
+            }
+
+            if let Some(mask) = entry.spec.password_mask() {
+                set_field_password(field.handle(), mask)?;
+            }
+
+            fields.push(label);
+            fields.push(field);
+
+            y += size.lines;
+        }
+
+        let mut form = Form::new(fields)?;
+
+        let size = scale_form(form.handle())?;
+        let window = newwin(Size { lines: size.lines + 2, columns: size.columns + 2 }, self.origin)?;
+        let subwindow = match derwin(window, size, Origin { y: 1, x: 1 }) {
+            Ok(subwindow) => subwindow,
+            Err(source)   => {
+                let _ = delwin(window);
+
+                return Err(source);
+            }
+        };
+
+        form.set_owned_windows(window, subwindow)?;
+        r#box(window, ChtypeChar::from(0), ChtypeChar::from(0))?;
+
+        Ok(form)
+    }
+}
+
+impl<T> Default for FormBuilder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[test]
+fn form_builder_test() {
+    use crate::form::{field_info, new_page};
+
+    crate::funcs::with_test_screen(|_| {
+        unsafe { libc::setlocale(libc::LC_CTYPE, c"C.UTF-8".as_ptr()) };
+
+        let form = FormBuilder::<()>::new()
+            .field("ポート番号", FieldSpec::text(10))
+            .field("Port", FieldSpec::text(5))
+            .field("Notes", FieldSpec::multi_line(3, 20))
+            .set_max_lines(6)
+            .build()
+            .unwrap();
+
+        let layout: Vec<(Origin, Size, bool)> = form.fields().iter().map(|field| {
+            let parameters = field_info(field.handle()).unwrap();
+
+            (parameters.origin(), parameters.size(), new_page(Some(field.handle())))
+        }).collect();
+
+        // the labels are as wide as the widest label in screen columns ("ポート番号" is 5 characters but 10 columns).
+        assert_eq!(layout[0], (Origin { y: 0, x: 0 }, Size { lines: 1, columns: 10 }, false));
+        assert_eq!(layout[1], (Origin { y: 0, x: 11 }, Size { lines: 1, columns: 10 }, false));
+        assert_eq!(layout[3], (Origin { y: 1, x: 11 }, Size { lines: 1, columns: 5 }, false));
+
+        // the multi-line field does not fit below the others (4 lines inside the border) so starts a new page.
+        assert_eq!(layout[4], (Origin { y: 0, x: 0 }, Size { lines: 1, columns: 10 }, true));
+        assert_eq!(layout[5], (Origin { y: 0, x: 11 }, Size { lines: 3, columns: 20 }, false));
+    });
+}
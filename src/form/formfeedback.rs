@@ -0,0 +1,151 @@
+/*
+    src/form/formfeedback.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use crate::{
+    normal,
+    NCurseswError, Origin, mvwaddstr, werase, wrefresh,
+    shims::ncurses::WINDOW,
+    form::{
+        FIELD, field_back, field_fore, field_type_message, set_field_back, set_field_fore
+    }
+};
+
+/// Validation feedback for a `Form` (see `Form::set_feedback()`).
+///
+/// When a field fails validation it is highlighted with the invalid field
+/// attributes and the message of its field type (see `FieldType::set_message()`)
+/// is displayed in the status window, once the field is valid it is restored
+/// to its original attributes and the status window is cleared.
+///
+/// ```text
+/// if let Some(field) = form.field_by_name("Port") {
+///     field.set_field_type(&FieldType::port().set_message("A port is between 1 and 65535"))?;
+/// }
+///
+/// form.set_feedback(FormFeedback::new().set_status_window(status));
+/// ```
+#[derive(Debug)]
+pub struct FormFeedback {
+    fore:            normal::Attributes,
+    back:            normal::Attributes,
+    status:          Option<WINDOW>,
+    default_message: String,
+    invalid:         Option<InvalidField>
+}
+
+// the field that is highlighted and its original attributes.
+#[derive(Debug)]
+struct InvalidField {
+    field: FIELD,
+    fore:  normal::Attributes,
+    back:  normal::Attributes
+}
+
+impl FormFeedback {
+    /// Feedback that highlights invalid fields in reverse video without a status window.
+    pub fn new() -> Self {
+        Self {
+            fore:            normal::Attributes::default() | normal::Attribute::Reverse,
+            back:            normal::Attributes::default() | normal::Attribute::Reverse,
+            status:          None,
+            default_message: "Invalid value".to_string(),
+            invalid:         None
+        }
+    }
+
+    /// Set the foreground attributes of an invalid field (see `set_field_fore()`).
+    pub fn set_fore(mut self, attrs: normal::Attributes) -> Self {
+        self.fore = attrs;
+
+        self
+    }
+
+    /// Set the background attributes of an invalid field (see `set_field_back()`).
+    pub fn set_back(mut self, attrs: normal::Attributes) -> Self {
+        self.back = attrs;
+
+        self
+    }
+
+    /// Set the window the message of an invalid field is displayed in.
+    pub fn set_status_window(mut self, window: WINDOW) -> Self {
+        self.status = Some(window);
+
+        self
+    }
+
+    /// Set the message displayed when the field type of an invalid field has no message.
+    pub fn set_default_message<S: Into<String>>(mut self, message: S) -> Self {
+        self.default_message = message.into();
+
+        self
+    }
+
+    /// The field currently highlighted as invalid.
+    pub fn invalid_field(&self) -> Option<FIELD> {
+        self.invalid.as_ref().map(|invalid| invalid.field)
+    }
+
+    // highlight an invalid field and display its message.
+    pub(in crate::form) fn show_invalid(&mut self, field: FIELD) -> result!(()) {
+        if self.invalid_field() != Some(field) {
+            self.clear_invalid()?;
+
+            self.invalid = Some(InvalidField { field, fore: field_fore(Some(field)), back: field_back(Some(field)) });
+
+            set_field_fore(Some(field), self.fore)?;
+            set_field_back(Some(field), self.back)?;
+        }
+
+        let message = field_type_message(field).unwrap_or_else(|| self.default_message.clone());
+
+        self.status_message(&message)
+    }
+
+    // restore the attributes of the highlighted field and clear its message.
+    pub(in crate::form) fn clear_invalid(&mut self) -> result!(()) {
+        if let Some(invalid) = self.invalid.take() {
+            set_field_fore(Some(invalid.field), invalid.fore)?;
+            set_field_back(Some(invalid.field), invalid.back)?;
+
+            self.status_message("")?;
+        }
+
+        Ok(())
+    }
+
+    fn status_message(&self, message: &str) -> result!(()) {
+        if let Some(status) = self.status {
+            werase(status)?;
+            mvwaddstr(status, Origin { y: 0, x: 0 }, message)?;
+            wrefresh(status)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for FormFeedback {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,192 @@
+/*
+    src/form/formhook.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{cell::RefCell, collections::{HashMap, HashSet}};
+use crate::form::{
+    FORM, NCurseswFormError,
+    set_field_init, set_field_term, set_form_init, set_form_term
+};
+
+type FormHookFn = Box<dyn FnMut(FORM)>;
+
+thread_local! {
+    // Rust closures registered as form hooks, keyed on the form handle and
+    // the hook type (see `menu::set_menu_init_fn()` for the rationale of
+    // holding these per thread).
+    static FORM_HOOKS: RefCell<HashMap<(usize, FormHookType), FormHookFn>> = RefCell::new(HashMap::new());
+
+    // The hooks that are being called, a hook is removed from this set when it
+    // is replaced or removed while it is being called so that the closure is
+    // not restored once the call returns.
+    static DISPATCHING_FORM_HOOKS: RefCell<HashSet<(usize, FormHookType)>> = RefCell::new(HashSet::new());
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum FormHookType {
+    FormInit,
+    FormTerm,
+    FieldInit,
+    FieldTerm
+}
+
+/// Sets a closure to be called at form-post time and just after a page change
+/// once it is posted.
+///
+/// This is the closure equivalent of `set_form_init()`, the closure is held by the
+/// crate until it is replaced, `clear_form_hooks()` is called or the form is freed
+/// with `free_form()`. Unlike `set_form_init()` a form must be specified.
+pub fn set_form_init_fn<F>(form: FORM, hook: F) -> form_result!(())
+    where F: FnMut(FORM) + 'static
+{
+    insert_form_hook(form, FormHookType::FormInit, Box::new(hook));
+
+    if let Err(source) = set_form_init(Some(form), Some(form_init_trampoline)) {
+        remove_form_hook(form, FormHookType::FormInit);
+
+        return Err(source);
+    }
+
+    Ok(())
+}
+
+/// Sets a closure to be called at form-unpost time and just before a page change
+/// once it is posted.
+///
+/// This is the closure equivalent of `set_form_term()`, see `set_form_init_fn()`
+/// for details of how long the closure is held.
+pub fn set_form_term_fn<F>(form: FORM, hook: F) -> form_result!(())
+    where F: FnMut(FORM) + 'static
+{
+    insert_form_hook(form, FormHookType::FormTerm, Box::new(hook));
+
+    if let Err(source) = set_form_term(Some(form), Some(form_term_trampoline)) {
+        remove_form_hook(form, FormHookType::FormTerm);
+
+        return Err(source);
+    }
+
+    Ok(())
+}
+
+/// Sets a closure to be called at form-post time and each time the selected
+/// field changes (after the change).
+///
+/// This is the closure equivalent of `set_field_init()`, see `set_form_init_fn()`
+/// for details of how long the closure is held.
+pub fn set_field_init_fn<F>(form: FORM, hook: F) -> form_result!(())
+    where F: FnMut(FORM) + 'static
+{
+    insert_form_hook(form, FormHookType::FieldInit, Box::new(hook));
+
+    if let Err(source) = set_field_init(Some(form), Some(field_init_trampoline)) {
+        remove_form_hook(form, FormHookType::FieldInit);
+
+        return Err(source);
+    }
+
+    Ok(())
+}
+
+/// Sets a closure to be called at form-unpost time and each time the selected
+/// field changes (before the change).
+///
+/// This is the closure equivalent of `set_field_term()`, see `set_form_init_fn()`
+/// for details of how long the closure is held.
+pub fn set_field_term_fn<F>(form: FORM, hook: F) -> form_result!(())
+    where F: FnMut(FORM) + 'static
+{
+    insert_form_hook(form, FormHookType::FieldTerm, Box::new(hook));
+
+    if let Err(source) = set_field_term(Some(form), Some(field_term_trampoline)) {
+        remove_form_hook(form, FormHookType::FieldTerm);
+
+        return Err(source);
+    }
+
+    Ok(())
+}
+
+/// Removes any closures set with `set_form_init_fn()`, `set_form_term_fn()`,
+/// `set_field_init_fn()` and `set_field_term_fn()` from the form and resets
+/// the form's hooks.
+pub fn clear_form_hooks(form: FORM) -> form_result!(()) {
+    set_form_init(Some(form), None)?;
+    set_form_term(Some(form), None)?;
+    set_field_init(Some(form), None)?;
+    set_field_term(Some(form), None)?;
+
+    remove_form_hooks(form);
+
+    Ok(())
+}
+
+// drop all closures held for the form, this is called by `free_form()` as
+// NCurses may reuse the form handle for a subsequent form.
+pub(in crate::form) fn remove_form_hooks(form: FORM) {
+    FORM_HOOKS.with(|hooks| hooks.borrow_mut().retain(|(handle, _), _| *handle != form as usize));
+    DISPATCHING_FORM_HOOKS.with(|keys| keys.borrow_mut().retain(|(handle, _)| *handle != form as usize));
+}
+
+fn insert_form_hook(form: FORM, hook_type: FormHookType, hook: FormHookFn) {
+    FORM_HOOKS.with(|hooks| hooks.borrow_mut().insert((form as usize, hook_type), hook));
+    DISPATCHING_FORM_HOOKS.with(|keys| keys.borrow_mut().remove(&(form as usize, hook_type)));
+}
+
+fn remove_form_hook(form: FORM, hook_type: FormHookType) {
+    FORM_HOOKS.with(|hooks| hooks.borrow_mut().remove(&(form as usize, hook_type)));
+    DISPATCHING_FORM_HOOKS.with(|keys| keys.borrow_mut().remove(&(form as usize, hook_type)));
+}
+
+fn dispatch_form_hook(form: FORM, hook_type: FormHookType) {
+    let key = (form as usize, hook_type);
+
+    // take the closure out of the map while it is being called so that the
+    // closure itself can call the form functions that may trigger a hook.
+    if let Some(mut hook) = FORM_HOOKS.with(|hooks| hooks.borrow_mut().remove(&key)) {
+        DISPATCHING_FORM_HOOKS.with(|keys| keys.borrow_mut().insert(key));
+
+        hook(form);
+
+        // restore the closure unless it was replaced or removed (by the
+        // closure itself clearing the hooks or freeing the form) while it
+        // was being called.
+        if DISPATCHING_FORM_HOOKS.with(|keys| keys.borrow_mut().remove(&key)) {
+            FORM_HOOKS.with(|hooks| hooks.borrow_mut().insert(key, hook));
+        }
+    }
+}
+
+extern "C" fn form_init_trampoline(form: FORM) {
+    dispatch_form_hook(form, FormHookType::FormInit);
+}
+
+extern "C" fn form_term_trampoline(form: FORM) {
+    dispatch_form_hook(form, FormHookType::FormTerm);
+}
+
+extern "C" fn field_init_trampoline(form: FORM) {
+    dispatch_form_hook(form, FormHookType::FieldInit);
+}
+
+extern "C" fn field_term_trampoline(form: FORM) {
+    dispatch_form_hook(form, FormHookType::FieldTerm);
+}
@@ -0,0 +1,333 @@
+/*
+    src/form/forminput.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{cell::RefCell, collections::HashSet, convert::TryFrom};
+use crate::{
+    CharacterResult, GetCharacterType, KeyBinding, NCurseswError, Origin, WideChar, getmaxyx,
+    funcs::window_screen_origin,
+    shims::{
+        nform,
+        constants::{OK, E_OK, E_INVALID_FIELD, E_REQUEST_DENIED, E_UNKNOWN_COMMAND}
+    },
+    form::{
+        FIELD, FORM, FormAction, FormInputResult, FormKeyMap, FormRequest, current_field,
+        field_info, field_opts, form_page, form_sub, is_field_password, is_field_revealed,
+        set_current_field, set_field_revealed, formpager::page_fields,
+        fieldtype::{resume_field_type, suspend_field_type},
+        ncurseswformerror::{NCurseswFormError, ncursesw_form_error_from_rc}
+    }
+};
+
+thread_local! {
+    // Forms that are in overlay mode (NCurses does not provide a way to query
+    // the mode of a form so it is tracked by `form_handle_input()`).
+    static OVERLAY_FORMS: RefCell<HashSet<usize>> = RefCell::new(HashSet::new());
+}
+
+/// Process an input event returned by the `getch()` or `get_wch()` families of
+/// functions for a posted form using the given key map.
+///
+/// Keys and characters bound in `keymap` are actioned, a `KeyBinding::MouseEvent`
+/// is passed to `form_driver()` so that a click on a field makes it the current
+/// field and any other printable character is entered into the current field
+/// with `form_driver_w()`.
+///
+/// The masks of password fields are not displayed by this function, see
+/// `draw_password_masks()`.
+///
+/// Requests that NCurses denies (for example a character rejected by the field
+/// type) are returned as `FormInputResult::Continue` and requests that fail
+/// because the current field is invalid are returned as `FormInputResult::Invalid`.
+///
+/// ```text
+/// let keymap = FormKeyMap::default().bind_key(KeyBinding::FunctionKey(10), FormAction::Submit);
+///
+/// loop {
+///     match form_handle_input(form, wget_wch(window)?, &keymap)? {
+///         FormInputResult::Submitted => break true,
+///         FormInputResult::Cancelled => break false,
+///         _                          => wrefresh(window)?
+///     }
+/// }
+/// ```
+pub fn form_handle_input<T>(form: FORM, event: CharacterResult<T>, keymap: &FormKeyMap) -> form_result!(FormInputResult)
+    where T:    GetCharacterType,
+          char: TryFrom<T>
+{
+    match event {
+        CharacterResult::Key(KeyBinding::MouseEvent) => form_mouse_event(form),
+        CharacterResult::Key(key)                    => {
+            keymap.key_action(key).map_or(Ok(FormInputResult::Unhandled), |action| form_action(form, action))
+        },
+        CharacterResult::Character(ch)               => {
+            let ch = match char::try_from(ch) {
+                Ok(ch) => ch,
+                Err(_) => return Ok(FormInputResult::Unhandled)
+            };
+
+            if let Some(action) = keymap.character_action(ch) {
+                form_action(form, action)
+            } else if !ch.is_control() {
+                form_character(form, ch)
+            } else {
+                Ok(FormInputResult::Unhandled)
+            }
+        }
+    }
+}
+
+/// Is the form in overlay mode, as opposed to insert mode (see `form_handle_input()`).
+pub fn is_form_overlay(form: FORM) -> bool {
+    OVERLAY_FORMS.with(|forms| forms.borrow().contains(&(form as usize)))
+}
+
+fn form_action(form: FORM, action: FormAction) -> form_result!(FormInputResult) {
+    match action {
+        FormAction::Request(request) => form_request(form, request),
+        FormAction::ToggleMode       => {
+            form_request(form, if is_form_overlay(form) { FormRequest::InsertMode } else { FormRequest::OverlayMode })
+        },
+        FormAction::ToggleReveal     => {
+            let field = current_field(Some(form))?;
+
+            if is_field_password(field) {
+                set_field_revealed(field, !is_field_revealed(field))?;
+            }
+
+            Ok(FormInputResult::Continue)
+        },
+        FormAction::Submit           => match form_request(form, FormRequest::Validate)? {
+            FormInputResult::Continue => Ok(FormInputResult::Submitted),
+            result                    => Ok(result)
+        },
+        FormAction::NewLineOrSubmit  => {
+            // a field with one row on screen is a single-line field even if it scrolls.
+            if field_info(current_field(Some(form))?)?.size().lines > 1 {
+                form_request(form, FormRequest::Newline)
+            } else {
+                form_action(form, FormAction::Submit)
+            }
+        },
+        FormAction::Cancel           => Ok(FormInputResult::Cancelled)
+    }
+}
+
+pub(in crate::form) fn form_request(form: FORM, request: FormRequest) -> form_result!(FormInputResult) {
+    match unsafe { nform::form_driver(form, request.value()?) } {
+        E_OK             => {
+            match request {
+                FormRequest::OverlayMode => set_form_overlay(form, true),
+                FormRequest::InsertMode  => set_form_overlay(form, false),
+                _                        => { }
+            }
+
+            Ok(FormInputResult::Continue)
+        },
+        E_REQUEST_DENIED => Ok(FormInputResult::Continue),
+        E_INVALID_FIELD  => Ok(FormInputResult::Invalid(current_field(Some(form))?)),
+        rc               => Err(form_function_error_with_rc!("form_driver", rc))
+    }
+}
+
+// characters are passed to `form_driver_w()` with a type of `OK` rather than
+// using `form::form_driver_w()` which always passes a request.
+fn form_character(form: FORM, ch: char) -> form_result!(FormInputResult) {
+    match unsafe { nform::form_driver_w(form, OK, WideChar::new(ch).into()) } {
+        E_OK | E_REQUEST_DENIED => Ok(FormInputResult::Continue),
+        E_INVALID_FIELD         => Ok(FormInputResult::Invalid(current_field(Some(form))?)),
+        rc                      => Err(form_function_error_with_rc!("form_driver_w", rc))
+    }
+}
+
+// `form_driver()` returns `E_REQUEST_DENIED` or `E_UNKNOWN_COMMAND` when the
+// click was not on a field of the form.
+fn form_mouse_event(form: FORM) -> form_result!(FormInputResult) {
+    match unsafe { nform::form_driver(form, FormRequest::Mouse.value()?) } {
+        E_OK                                 => Ok(FormInputResult::Continue),
+        E_REQUEST_DENIED | E_UNKNOWN_COMMAND => Ok(FormInputResult::Unhandled),
+        E_INVALID_FIELD                      => Ok(FormInputResult::Invalid(current_field(Some(form))?)),
+        rc                                   => Err(form_function_error_with_rc!("form_driver", rc))
+    }
+}
+
+// make the field of the current page at a screen position the current field,
+// this mirrors the handling of a click by `form_driver()` for a mouse event
+// that has already been read with `getmouse()`.
+pub(in crate::form) fn form_mouse_click(form: FORM, origin: Origin) -> result!(FormInputResult) {
+    let window = form_sub(Some(form))?;
+    let (window_origin, size) = (window_screen_origin(window)?, getmaxyx(window)?);
+    let (y, x) = (origin.y - window_origin.y, origin.x - window_origin.x);
+
+    if !(0..size.lines).contains(&y) || !(0..size.columns).contains(&x) {
+        return Ok(FormInputResult::Unhandled);
+    }
+
+    let page = usize::try_from(form_page(Some(form))?)?;
+
+    let field = page_fields(form)?.into_iter().nth(page).unwrap_or_default().into_iter().find(|field| {
+        let opts = field_opts(Some(*field));
+
+        opts.is_active() && opts.is_visible() && field_info(*field).is_ok_and(|info| {
+            let (origin, size) = (info.origin(), info.size());
+
+            (origin.y..origin.y + size.lines).contains(&y) && (origin.x..origin.x + size.columns).contains(&x)
+        })
+    });
+
+    match field.map(|field| set_current_field(form, field)) {
+        Some(Ok(()))                                       |
+        Some(Err(NCurseswFormError::RequestDenied { .. })) => Ok(FormInputResult::Continue),
+        Some(Err(NCurseswFormError::InvalidField { .. }))  => Ok(FormInputResult::Invalid(current_field(Some(form))?)),
+        Some(Err(source))                                  => Err(NCurseswError::from(source)),
+        None                                               => Ok(FormInputResult::Unhandled)
+    }
+}
+
+// validate the current field and then each selectable field in turn leaving
+// the first invalid field as the current field, otherwise the current field
+// is restored.
+pub(in crate::form) fn validate_fields(form: FORM, fields: &[FIELD]) -> form_result!(Option<FIELD>) {
+    let current = current_field(Some(form))?;
+
+    if let FormInputResult::Invalid(field) = form_request(form, FormRequest::Validate)? {
+        return Ok(Some(field));
+    }
+
+    for &field in fields {
+        let opts = field_opts(Some(field));
+
+        if field == current || !opts.is_active() || !opts.is_visible() {
+            continue;
+        }
+
+        set_current_field(form, field)?;
+
+        if let FormInputResult::Invalid(field) = form_request(form, FormRequest::Validate)? {
+            return Ok(Some(field));
+        }
+    }
+
+    set_current_field(form, current)?;
+
+    Ok(None)
+}
+
+// validate the current field and then each selectable field in turn returning
+// all of the invalid fields, the field type of an invalid field is suspended
+// while the remaining fields are validated so that the field can be left.
+// The current field is restored.
+pub(in crate::form) fn invalid_fields(form: FORM, fields: &[FIELD]) -> form_result!(Vec<FIELD>) {
+    let current = current_field(Some(form))?;
+    let mut invalid = vec![];
+    let mut suspended = vec![];
+
+    let result = (|| {
+        for &field in std::iter::once(&current).chain(fields.iter().filter(|field| **field != current)) {
+            let opts = field_opts(Some(field));
+
+            if field != current {
+                if !opts.is_active() || !opts.is_visible() {
+                    continue;
+                }
+
+                set_current_field(form, field)?;
+            }
+
+            if let FormInputResult::Invalid(field) = form_request(form, FormRequest::Validate)? {
+                invalid.push(field);
+
+                // a field without a field type set with `set_field_type()`
+                // can't be left so stop validating.
+                if !suspend_field_type(field)? {
+                    break;
+                }
+
+                suspended.push(field);
+            }
+        }
+
+        match set_current_field(form, current) {
+            Ok(()) | Err(NCurseswFormError::InvalidField { .. }) => Ok(()),
+            Err(source)                                         => Err(source)
+        }
+    })();
+
+    for field in suspended {
+        resume_field_type(field)?;
+    }
+
+    result.map(|_| invalid)
+}
+
+fn set_form_overlay(form: FORM, overlay: bool) {
+    OVERLAY_FORMS.with(|forms| {
+        let mut forms = forms.borrow_mut();
+
+        if overlay {
+            forms.insert(form as usize);
+        } else {
+            forms.remove(&(form as usize));
+        }
+    });
+}
+
+// forget the mode of the form, this is called by `free_form()` as NCurses
+// may reuse the form handle for a subsequent form.
+pub(in crate::form) fn remove_form_overlay(form: FORM) {
+    set_form_overlay(form, false);
+}
+
+#[test]
+fn form_new_line_or_submit_test() {
+    use crate::{
+        Size, funcs::with_test_screen,
+        form::{FieldParameters, field_value, free_field, free_form, new_field, new_form, post_form, unpost_form}
+    };
+
+    with_test_screen(|_| {
+        let notes = new_field(FieldParameters::new(Size { lines: 2, columns: 10 }, Origin { y: 0, x: 0 }, 0, 0)).unwrap();
+        let name = new_field(FieldParameters::new(Size { lines: 1, columns: 10 }, Origin { y: 3, x: 0 }, 0, 0)).unwrap();
+        let mut fields = vec![notes, name];
+        let form = new_form(&mut fields).unwrap();
+        let keymap = FormKeyMap::default();
+
+        post_form(form).unwrap();
+
+        let input = |event: CharacterResult<char>| form_handle_input(form, event, &keymap).unwrap();
+
+        // enter starts a new line in a multi-line field.
+        for event in [CharacterResult::Character('a'), CharacterResult::Key(KeyBinding::Enter), CharacterResult::Character('b')] {
+            assert_eq!(input(event), FormInputResult::Continue);
+        }
+
+        assert_eq!(input(CharacterResult::Character('\t')), FormInputResult::Continue);
+        assert_eq!(field_value(notes, 0, true).unwrap(), "a\nb");
+
+        // and submits the form from a single-line field.
+        assert_eq!(input(CharacterResult::Character('\r')), FormInputResult::Submitted);
+
+        unpost_form(form).unwrap();
+        free_form(form).unwrap();
+        free_field(notes).unwrap();
+        free_field(name).unwrap();
+    });
+}
@@ -0,0 +1,39 @@
+/*
+    src/form/forminputresult.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use crate::form::FIELD;
+
+/// The result of passing an input event to `form_handle_input()`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FormInputResult {
+    /// The form was submitted and the current field is valid.
+    Submitted,
+    /// The form was cancelled.
+    Cancelled,
+    /// The contents of the current field are invalid so the request (i.e.
+    /// moving to another field or submitting the form) was not carried out.
+    Invalid(FIELD),
+    /// The event was not handled by the form.
+    Unhandled,
+    /// The event was handled by the form, continue processing input.
+    Continue
+}
@@ -0,0 +1,119 @@
+/*
+    src/form/formkeymap.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::collections::HashMap;
+use crate::{KeyBinding, form::{FormAction, FormRequest}};
+
+/// Key map used by `form_handle_input()` to translate keys and characters
+/// into `FormAction`'s.
+///
+/// The default key map binds tab, back-tab and the up/down arrows to moving
+/// between fields, the left/right arrows, home/end and the emacs style control
+/// characters to editing the current field, page up/down to changing page, the
+/// insert key to `FormAction::ToggleMode`, Ctrl-R to `FormAction::ToggleReveal`,
+/// enter to `FormAction::NewLineOrSubmit` (so that enter starts a new line in a
+/// multi-line field and submits the form otherwise) and escape to
+/// `FormAction::Cancel`. Any other printable character is entered into the
+/// current field.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FormKeyMap {
+    keys:       HashMap<KeyBinding, FormAction>,
+    characters: HashMap<char, FormAction>
+}
+
+impl FormKeyMap {
+    /// An empty key map.
+    pub fn new() -> Self {
+        Self { keys: HashMap::new(), characters: HashMap::new() }
+    }
+
+    /// Bind a key to an action, replacing any existing binding.
+    pub fn bind_key(mut self, key: KeyBinding, action: FormAction) -> Self {
+        self.keys.insert(key, action);
+
+        self
+    }
+
+    /// Bind a character to an action, replacing any existing binding.
+    pub fn bind_character(mut self, ch: char, action: FormAction) -> Self {
+        self.characters.insert(ch, action);
+
+        self
+    }
+
+    /// Remove the binding of a key.
+    pub fn unbind_key(mut self, key: KeyBinding) -> Self {
+        self.keys.remove(&key);
+
+        self
+    }
+
+    /// Remove the binding of a character.
+    pub fn unbind_character(mut self, ch: char) -> Self {
+        self.characters.remove(&ch);
+
+        self
+    }
+
+    /// Returns the action bound to a key.
+    pub fn key_action(&self, key: KeyBinding) -> Option<FormAction> {
+        self.keys.get(&key).copied()
+    }
+
+    /// Returns the action bound to a character.
+    pub fn character_action(&self, ch: char) -> Option<FormAction> {
+        self.characters.get(&ch).copied()
+    }
+}
+
+impl Default for FormKeyMap {
+    fn default() -> Self {
+        Self::new()
+            .bind_key(KeyBinding::UpArrow, FormAction::Request(FormRequest::PreviousField))
+            .bind_key(KeyBinding::DownArrow, FormAction::Request(FormRequest::NextField))
+            .bind_key(KeyBinding::LeftArrow, FormAction::Request(FormRequest::LeftCharacter))
+            .bind_key(KeyBinding::RightArrow, FormAction::Request(FormRequest::RightCharacter))
+            .bind_key(KeyBinding::Home, FormAction::Request(FormRequest::BeginLine))
+            .bind_key(KeyBinding::End, FormAction::Request(FormRequest::EndLine))
+            .bind_key(KeyBinding::PreviousPage, FormAction::Request(FormRequest::PreviousPage))
+            .bind_key(KeyBinding::NextPage, FormAction::Request(FormRequest::NextPage))
+            .bind_key(KeyBinding::BackTab, FormAction::Request(FormRequest::PreviousField))
+            .bind_key(KeyBinding::Backspace, FormAction::Request(FormRequest::DeletePreviousCharacter))
+            .bind_key(KeyBinding::DeleteCharacter, FormAction::Request(FormRequest::DeleteCharacter))
+            .bind_key(KeyBinding::InsertCharacter, FormAction::ToggleMode)
+            .bind_key(KeyBinding::Enter, FormAction::NewLineOrSubmit)
+            .bind_key(KeyBinding::Cancel, FormAction::Cancel)
+            .bind_key(KeyBinding::Exit, FormAction::Cancel)
+            .bind_character('\t', FormAction::Request(FormRequest::NextField))
+            .bind_character('\x08', FormAction::Request(FormRequest::DeletePreviousCharacter))
+            .bind_character('\x7f', FormAction::Request(FormRequest::DeletePreviousCharacter))
+            .bind_character('\x01', FormAction::Request(FormRequest::BeginLine))        // Ctrl-A
+            .bind_character('\x05', FormAction::Request(FormRequest::EndLine))          // Ctrl-E
+            .bind_character('\x0b', FormAction::Request(FormRequest::ClearToEndOfLine)) // Ctrl-K
+            .bind_character('\x15', FormAction::Request(FormRequest::ClearField))       // Ctrl-U
+            .bind_character('\x17', FormAction::Request(FormRequest::DeleteWord))       // Ctrl-W
+            .bind_character('\x12', FormAction::ToggleReveal)                           // Ctrl-R
+            .bind_character('\n', FormAction::NewLineOrSubmit)
+            .bind_character('\r', FormAction::NewLineOrSubmit)
+            .bind_character('\x1b', FormAction::Cancel)
+    }
+}
@@ -0,0 +1,325 @@
+/*
+    src/form/formpager.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::convert::TryFrom;
+use crate::{
+    normal,
+    CharacterResult, GetCharacterType, KeyBinding, NCurseswError, Origin,
+    getbegyx, mvwaddstr, wattroff, wattron,
+    funcs::str_width,
+    shims::{bindings, ncurses::WINDOW},
+    mouse::{BUTTON1_CLICKED, BUTTON1_DOUBLE_CLICKED, mmask_t, getmouse, wenclose},
+    form::{
+        FIELD, FORM, FormAction, FormInputResult, FormKeyMap, FormPagerStyle, FormRequest,
+        NCurseswFormError, form_fields, form_handle_input, form_page, form_win, new_page,
+        draw_password_masks, set_form_page, forminput::{form_mouse_click, validate_fields}
+    }
+};
+
+/// Page navigation for a form with more than one page (see `set_new_page()`).
+///
+/// The pager displays which page is current either as a "Page 2/4" indicator
+/// or as a tab strip, by default on the top line of the form's window (i.e.
+/// the border of a form created by `FormBuilder`). Requests to change page
+/// (by default the page up/down keys, see `FormKeyMap`) are carried out by the
+/// pager which validates all of the fields of the current page before leaving it.
+///
+/// ```text
+/// let pager = FormPager::new(FormPagerStyle::Tabs(vec!["Server".to_string(), "Client".to_string()]));
+///
+/// pager.draw(form)?;
+///
+/// loop {
+///     match pager.handle_input(form, wget_wch(window)?, &keymap)? {
+///         FormInputResult::Submitted => break,
+///         _                          => wrefresh(window)?
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct FormPager {
+    style:     FormPagerStyle,
+    window:    Option<WINDOW>,
+    origin:    Origin,
+    highlight: normal::Attributes
+}
+
+impl FormPager {
+    /// Create a pager of a style.
+    pub fn new(style: FormPagerStyle) -> Self {
+        Self {
+            style,
+            window:    None,
+            origin:    Origin { y: 0, x: 2 },
+            highlight: normal::Attributes::default() | normal::Attribute::Reverse
+        }
+    }
+
+    /// Set the window the pager is displayed in, the default is the form's window.
+    pub fn set_window(mut self, window: WINDOW) -> Self {
+        self.window = Some(window);
+
+        self
+    }
+
+    /// Set the origin of the pager within its window, the default is `Origin { y: 0, x: 2 }`.
+    pub fn set_origin(mut self, origin: Origin) -> Self {
+        self.origin = origin;
+
+        self
+    }
+
+    /// Set the attributes of the current page's tab.
+    pub fn set_highlight(mut self, attrs: normal::Attributes) -> Self {
+        self.highlight = attrs;
+
+        self
+    }
+
+    /// Display the current page of the form.
+    pub fn draw(&self, form: FORM) -> result!(()) {
+        let window = self.window(form)?;
+        let page = form_page(Some(form))?;
+        let count = form_page_count(form)?;
+
+        match &self.style {
+            FormPagerStyle::Indicator   => {
+                let width = str_width(&format!(" Page {}/{} ", count, count));
+
+                mvwaddstr(window, self.origin, format!("{:<width$}", format!(" Page {}/{} ", page + 1, count), width = width))?;
+            },
+            FormPagerStyle::Tabs(_)     => {
+                for (index, (title, origin)) in self.tabs(count).into_iter().enumerate() {
+                    let current = i32::try_from(index)? == page;
+
+                    if current {
+                        wattron(window, self.highlight)?;
+                    }
+
+                    mvwaddstr(window, origin, title)?;
+
+                    if current {
+                        wattroff(window, self.highlight)?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Change to a page of the form once the fields of the current page are valid,
+    /// `FormInputResult::Invalid` is returned with the first invalid field
+    /// (which becomes the current field) if they are not.
+    pub fn set_page(&self, form: FORM, page: i32) -> result!(FormInputResult) {
+        let pages = page_fields(form)?;
+        let current = form_page(Some(form))?;
+
+        if current != page && usize::try_from(page).is_ok_and(|page| page < pages.len()) {
+            if let Some(field) = validate_fields(form, &pages[usize::try_from(current)?])? {
+                return Ok(FormInputResult::Invalid(field));
+            }
+
+            set_form_page(form, page)?;
+        }
+
+        self.draw(form)?;
+
+        Ok(FormInputResult::Continue)
+    }
+
+    /// Process an input event for a posted form (see `form_handle_input()`),
+    /// page requests are carried out with `FormPager::set_page()` and a mouse
+    /// click on a tab changes to its page. The pager (and the masks of any
+    /// password fields, see `draw_password_masks()`) is redrawn after each event.
+    pub fn handle_input<E>(&self, form: FORM, event: CharacterResult<E>, keymap: &FormKeyMap) -> result!(FormInputResult)
+        where E:    GetCharacterType + Copy,
+              char: TryFrom<E>
+    {
+        if matches!(event, CharacterResult::Key(KeyBinding::MouseEvent)) {
+            let result = self.mouse_event(form)?;
+
+            draw_password_masks(form)?;
+
+            return Ok(result);
+        }
+
+        let action = match event {
+            CharacterResult::Key(key)      => keymap.key_action(key),
+            CharacterResult::Character(ch) => char::try_from(ch).ok().and_then(|ch| keymap.character_action(ch))
+        };
+
+        let count = form_page_count(form)?;
+        let page = form_page(Some(form))?;
+
+        let result = match action {
+            Some(FormAction::Request(FormRequest::NextPage))     => self.set_page(form, (page + 1) % count)?,
+            Some(FormAction::Request(FormRequest::PreviousPage)) => self.set_page(form, (page + count - 1) % count)?,
+            Some(FormAction::Request(FormRequest::FirstPage))    => self.set_page(form, 0)?,
+            Some(FormAction::Request(FormRequest::LastPage))     => self.set_page(form, count - 1)?,
+            _                                                    => {
+                // the page may change when moving between fields.
+                let result = form_handle_input(form, event, keymap)?;
+
+                self.draw(form)?;
+
+                result
+            }
+        };
+
+        draw_password_masks(form)?;
+
+        Ok(result)
+    }
+
+    fn mouse_event(&self, form: FORM) -> result!(FormInputResult) {
+        let window = self.window(form)?;
+        let mut event = bindings::MEVENT { id: 0, x: 0, y: 0, z: 0, bstate: 0 };
+
+        getmouse(&mut event)?;
+
+        if matches!(self.style, FormPagerStyle::Tabs(_)) && wenclose(window, Origin { y: event.y, x: event.x }) {
+            let begin = getbegyx(window)?;
+            let origin = Origin { y: event.y - begin.y, x: event.x - begin.x };
+            let tabs = self.tabs(form_page_count(form)?);
+
+            if let Some(page) = tabs.iter().position(|(title, tab)| {
+                origin.y == tab.y && origin.x >= tab.x && usize::try_from(origin.x - tab.x).is_ok_and(|x| x < str_width(title))
+            }) {
+                return self.set_page(form, i32::try_from(page)?);
+            }
+        }
+
+        // the event has already been read so the field is found here rather than
+        // by `form_driver()` (which would read the event again).
+        if event.bstate & (BUTTON1_CLICKED | BUTTON1_DOUBLE_CLICKED) as mmask_t == 0 {
+            return Ok(FormInputResult::Unhandled);
+        }
+
+        let result = form_mouse_click(form, Origin { y: event.y, x: event.x })?;
+
+        self.draw(form)?;
+
+        Ok(result)
+    }
+
+    fn window(&self, form: FORM) -> form_result!(WINDOW) {
+        self.window.map_or_else(|| form_win(Some(form)), Ok)
+    }
+
+    // the title and origin of each tab.
+    fn tabs(&self, count: i32) -> Vec<(String, Origin)> {
+        let titles = match &self.style {
+            FormPagerStyle::Tabs(titles) => titles.as_slice(),
+            FormPagerStyle::Indicator    => &[]
+        };
+
+        let mut origin = self.origin;
+
+        (0..count.max(0) as usize).map(|page| {
+            let title = format!(" {} ", titles.get(page).cloned().unwrap_or_else(|| format!("Page {}", page + 1)));
+            let tab = (title, origin);
+
+            origin.x += str_width(&tab.0) as i32;
+
+            tab
+        }).collect()
+    }
+}
+
+/// Returns the number of pages of a form.
+pub fn form_page_count(form: FORM) -> form_result!(i32) {
+    Ok(i32::try_from(page_fields(form)?.len())?)
+}
+
+// the fields of each page of a form, the first field always starts the first page.
+pub(in crate::form) fn page_fields(form: FORM) -> form_result!(Vec<Vec<FIELD>>) {
+    let mut pages: Vec<Vec<FIELD>> = vec![vec![]];
+
+    for (index, field) in form_fields(Some(form))?.into_iter().enumerate() {
+        if index > 0 && new_page(Some(field)) {
+            pages.push(vec![]);
+        }
+
+        if let Some(page) = pages.last_mut() {
+            page.push(field);
+        }
+    }
+
+    Ok(pages)
+}
+
+#[test]
+fn form_pager_mouse_test() {
+    use crate::{
+        Size, stdscr, nodelay, wget_wch,
+        funcs::with_test_screen,
+        mouse::{ALL_MOUSE_EVENTS, mousemask, ungetmouse},
+        form::{FieldParameters, current_field, free_field, free_form, new_field, new_form, post_form, set_new_page, unpost_form}
+    };
+
+    with_test_screen(|_| {
+        unsafe { libc::setlocale(libc::LC_CTYPE, c"C.UTF-8".as_ptr()) };
+
+        mousemask(ALL_MOUSE_EVENTS as mmask_t).unwrap();
+        nodelay(stdscr(), true).unwrap();
+
+        let field = |y: i32| new_field(FieldParameters::new(Size { lines: 1, columns: 10 }, Origin { y, x: 0 }, 0, 0)).unwrap();
+        let mut fields = vec![field(2), field(4), field(2)];
+
+        set_new_page(Some(fields[2]), true).unwrap();
+
+        let form = new_form(&mut fields).unwrap();
+        let pager = FormPager::new(FormPagerStyle::Tabs(vec!["東京".to_string(), "Two".to_string()]));
+        let keymap = FormKeyMap::default();
+
+        post_form(form).unwrap();
+        pager.draw(form).unwrap();
+
+        let click = |y: i32, x: i32| {
+            let mut event = bindings::MEVENT { id: 0, x, y, z: 0, bstate: BUTTON1_CLICKED as mmask_t };
+
+            ungetmouse(&mut event).unwrap();
+            pager.handle_input(form, wget_wch(stdscr()).unwrap(), &keymap).unwrap()
+        };
+
+        // click the second field of the first page then the tab of the second page
+        // (the tabs are " 東京 " in columns 2 to 7 and " Two " in columns 8 to 12).
+        assert_eq!(click(4, 3), FormInputResult::Continue);
+        assert_eq!(current_field(Some(form)).unwrap(), fields[1]);
+        assert_eq!(click(0, 7), FormInputResult::Continue);
+        assert_eq!(form_page(Some(form)).unwrap(), 0);
+        assert_eq!(click(0, 10), FormInputResult::Continue);
+        assert_eq!(form_page(Some(form)).unwrap(), 1);
+        assert_eq!(click(10, 50), FormInputResult::Unhandled);
+
+        // no mouse events were left in the input queue.
+        assert!(wget_wch(stdscr()).is_err());
+
+        unpost_form(form).unwrap();
+        free_form(form).unwrap();
+
+        for field in fields {
+            free_field(field).unwrap();
+        }
+    });
+}
@@ -0,0 +1,139 @@
+/*
+    src/form/formstruct.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{fmt::Display, str::FromStr, collections::HashMap};
+use zeroize::Zeroize;
+use crate::{
+    NCurseswError,
+    form::{FieldSpec, Form, FormBuilder, NCurseswFormError, is_field_password}
+};
+
+/// A struct edited with a `Form`, each named field of the form holds the
+/// value of a member of the struct.
+///
+/// This trait is normally implemented with `#[derive(Form)]` (the `derive`
+/// feature), the members of the struct are annotated with the label of their
+/// field, its width and optionally a field type (see `FieldType`).
+///
+/// ```text
+/// #[derive(Form)]
+/// struct Config {
+///     #[form(label = "Host name", width = 30)]
+///     host: String,
+///     #[form(label = "Port", width = 5, validator = FieldType::port())]
+///     port: u16,
+///     #[form(label = "Password", width = 20, password, mask = '*')]
+///     password: String,
+///     #[form(skip)]
+///     retries: u32
+/// }
+///
+/// let form = config.to_form()?;
+/// ...
+/// let config = Config::from_form(&form)?;
+/// ```
+pub trait FormStruct: Sized {
+    /// The labels and specifications of the fields in the order they are laid out.
+    fn field_specs() -> Vec<(String, FieldSpec)>;
+
+    /// The values of the fields as pairs of labels and values.
+    fn field_values(&self) -> Vec<(String, String)>;
+
+    /// Create the struct from pairs of labels and values, if any value can not
+    /// be parsed then `NCurseswFormError::ParseFields` is returned with the
+    /// label of each field that failed and the reason.
+    fn from_field_values(values: &HashMap<String, String>) -> form_result!(Self);
+
+    /// A `FormBuilder` with the fields of the struct.
+    fn form_builder<T>() -> FormBuilder<T> {
+        Self::field_specs()
+            .into_iter()
+            .fold(FormBuilder::new(), |builder, (label, spec)| builder.field(label, spec))
+    }
+
+    /// Build a form with the fields of the struct loaded with its values.
+    fn to_form(&self) -> result!(Form) {
+        let form = Self::form_builder().build()?;
+
+        form.load_values(self.field_values())?;
+
+        Ok(form)
+    }
+
+    /// Create the struct from the values of a form (see `Form::save_values()`).
+    ///
+    /// The passwords of password fields are included, the buffers of those
+    /// fields are cleared (see `field_secret()`).
+    fn from_form<T>(form: &Form<T>) -> form_result!(Self) {
+        let mut values: HashMap<String, String> = form.save_values()?;
+
+        for field in form.fields().iter().filter(|field| is_field_password(field.handle())) {
+            if let Some(name) = field.name() {
+                values.insert(name.to_string(), field.secret()?.to_string());
+            }
+        }
+
+        let from_values = Self::from_field_values(&values);
+
+        values.values_mut().for_each(Zeroize::zeroize);
+
+        from_values
+    }
+}
+
+/// Parse the value of a field, the pair of the field's label and the reason is
+/// returned if the field is missing or can not be parsed (see `FormStruct`).
+pub fn parse_field_value<V>(values: &HashMap<String, String>, label: &str) -> Result<V, (String, String)>
+    where V:      FromStr,
+          V::Err: Display
+{
+    match values.get(label) {
+        Some(value) => value.parse().map_err(|source: V::Err| (label.to_string(), source.to_string())),
+        None        => Err((label.to_string(), "missing field".to_string()))
+    }
+}
+
+/// Parse the value of an optional field, an empty field is `None` (see `parse_field_value()`).
+pub fn parse_optional_field_value<V>(values: &HashMap<String, String>, label: &str) -> Result<Option<V>, (String, String)>
+    where V:      FromStr,
+          V::Err: Display
+{
+    match values.get(label) {
+        Some(value) if value.is_empty() => Ok(None),
+        _                               => parse_field_value(values, label).map(Some)
+    }
+}
+
+#[test]
+fn parse_field_value_test() {
+    let values: HashMap<String, String> = vec![
+        ("Port".to_string(), "8080".to_string()),
+        ("Retries".to_string(), "many".to_string()),
+        ("Timeout".to_string(), String::new())
+    ].into_iter().collect();
+
+    assert_eq!(parse_field_value::<u16>(&values, "Port"), Ok(8080));
+    assert_eq!(parse_field_value::<u32>(&values, "Retries"), Err(("Retries".to_string(), "invalid digit found in string".to_string())));
+    assert_eq!(parse_field_value::<u32>(&values, "Host"), Err(("Host".to_string(), "missing field".to_string())));
+    assert_eq!(parse_optional_field_value::<u32>(&values, "Timeout"), Ok(None));
+    assert_eq!(parse_optional_field_value::<u16>(&values, "Port"), Ok(Some(8080)));
+}
@@ -27,7 +27,7 @@ use crate::{
     Origin, Size, WideChar,
     cstring::*,
     shims::{
-        nform, nform::FieldType, ncurses::{SCREEN, WINDOW}, bindings::va_list,
+        nform, ncurses::{SCREEN, WINDOW}, bindings::va_list,
         constants::{
             E_OK, E_UNKNOWN_COMMAND, E_NO_MATCH, NO_JUSTIFICATION, JUSTIFY_LEFT,
             JUSTIFY_CENTER, JUSTIFY_RIGHT
@@ -35,7 +35,11 @@ use crate::{
     },
     form::{
         FormOptions, FormRequest, FieldInfo,
-        FieldJustification, FieldOptions, FieldParameters,
+        FieldJustification, FieldOptions, FieldParameters, FieldType,
+        fieldtype::{retain_field_type, release_field_type},
+        formhook::remove_form_hooks,
+        forminput::remove_form_overlay,
+        passwordfield::remove_field_password,
         ncurseswformerror::{
             NCurseswFormError, ncursesw_form_error_system_error,
             ncursesw_form_error_from_rc
@@ -416,9 +420,16 @@ pub fn form_win(form: Option<FORM>) -> form_result!(WINDOW) {
 }
 
 /// De-allocates storage associated with a field.
+///
+/// Any field type set with `set_field_type()` is also released.
 pub fn free_field(field: FIELD) -> form_result!(()) {
     match unsafe { nform::free_field(field) } {
-        E_OK => Ok(()),
+        E_OK => {
+            release_field_type(field);
+            remove_field_password(field);
+
+            Ok(())
+        },
         rc   => Err(form_function_error_with_rc!("free_field", rc))
     }
 }
@@ -432,9 +443,16 @@ pub fn free_fieldtype(fieldtype: FIELDTYPE) -> form_result!(()) {
 
 /// The function `free_form()` disconnects form from its field array and
 /// frees the storage allocated for the form.
+///
+/// Any closures set with the `set_*_fn()` hook functions are also released.
 pub fn free_form(form: FORM) -> form_result!(()) {
     match unsafe { nform::free_form(form) } {
-        E_OK => Ok(()),
+        E_OK => {
+            remove_form_hooks(form);
+            remove_form_overlay(form);
+
+            Ok(())
+        },
         rc   => Err(form_function_error_with_rc!("free_form", rc))
     }
 }
@@ -654,10 +672,17 @@ pub fn set_field_term(form: Option<FORM>, func: Form_Hook) -> form_result!(()) {
 /// Declares a data type for a given form field.
 /// This is the type checked by validation functions.
 ///
+/// The field type is kept (along with any memory it owns) until the field's
+/// type is replaced or the field is freed with `free_field()`.
+///
 /// If `field` is `None` then the default value is set.
-pub fn set_field_type(field: Option<FIELD>, fieldtype: FieldType) -> form_result!(()) {
-    match unsafe { nform::set_field_type(field, fieldtype) } {
-        E_OK => Ok(()),
+pub fn set_field_type(field: Option<FIELD>, fieldtype: &FieldType) -> form_result!(()) {
+    match fieldtype.set(field)? {
+        E_OK => {
+            retain_field_type(field, fieldtype);
+
+            Ok(())
+        },
         rc   => Err(form_function_error_with_rc!("set_field_type", rc))
     }
 }
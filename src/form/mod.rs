@@ -20,19 +20,44 @@
     IN THE SOFTWARE.
 */
 
+mod fieldchecks;
+mod fieldcompleter;
 mod fieldinfo;
 mod fieldjustification;
 mod fieldoption;
 mod fieldoptions;
 mod fieldparameters;
+mod fieldspec;
+mod fieldtype;
+mod fieldvalidator;
+mod fieldvalue;
+mod formaction;
+mod formbuilder;
+mod formfeedback;
+mod formhook;
+mod forminput;
+mod forminputresult;
+mod formkeymap;
 mod formoption;
 mod formoptions;
+mod formpager;
+mod formpagerstyle;
 mod formrequest;
+mod formstruct;
 mod funcs;
 mod ncurseswformerror;
+mod ownedfield;
+mod ownedform;
+mod passwordfield;
 
 pub use self::{
-    fieldinfo::*, fieldjustification::*, fieldoption::*, fieldoptions::*,
-    fieldparameters::*, formoption::*, formoptions::*, formrequest::*,
-    funcs::*, ncurseswformerror::*
+    fieldcompleter::*, fieldinfo::*, fieldjustification::*, fieldoption::*,
+    fieldoptions::*, fieldparameters::*, fieldspec::*, fieldtype::*, fieldvalidator::*,
+    fieldvalue::*, formaction::*, formbuilder::*, formfeedback::*, formhook::*,
+    forminput::*, forminputresult::*, formkeymap::*, formoption::*, formoptions::*,
+    formpager::*, formpagerstyle::*, formrequest::*, formstruct::*, funcs::*,
+    ncurseswformerror::*, ownedfield::*, ownedform::*, passwordfield::*
 };
+
+#[cfg(feature = "derive")]
+pub use ncursesw_derive::Form;
@@ -40,6 +40,12 @@ pub enum NCurseswFormError {
     Current { func: String },
     #[error("nform::{func}() : invalid field")]
     InvalidField { func: String },
+    /// The named fields failed validation.
+    #[error("nform::{func}() : invalid fields {fields:?}")]
+    InvalidFields { func: String, fields: Vec<String> },
+    /// The labelled fields could not be parsed, each with the reason.
+    #[error("nform::{func}() : unable to parse fields {fields:?}")]
+    ParseFields { func: String, fields: Vec<(String, String)> },
     #[error("nform::{func}() : not connected")]
     NotConnected { func: String },
     #[error("nform::{func}() : not posted")]
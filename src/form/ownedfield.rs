@@ -0,0 +1,134 @@
+/*
+    src/form/ownedfield.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use crate::{
+    Origin,
+    form::{
+        FIELD, FieldParameters, FieldType, NCurseswFormError,
+        dup_field, field_secret, field_value, free_field, link_field, new_field, set_field_type,
+        set_field_value
+    }
+};
+use zeroize::Zeroizing;
+
+/// A form field that owns its NCurses field handle and some typed user data.
+///
+/// The field is freed with `free_field()` when it is dropped, a field that
+/// is connected to a `Form` is owned by the form and is freed after the form.
+/// The user data replaces `set_field_userptr()` and is dropped with the field.
+///
+/// ```text
+/// let name = Field::with_data(FieldParameters::new(Size { lines: 1, columns: 20 }, Origin { y: 0, x: 10 }, 0, 0), "name")?;
+/// let form = Form::new(vec![name])?;
+/// ```
+#[derive(Debug)]
+pub struct Field<T = ()> {
+    handle: FIELD,
+    name:   Option<String>,
+    data:   Option<T>
+}
+
+impl<T> Field<T> {
+    /// Create a new field with no user data (see `new_field()`).
+    pub fn new(parameters: FieldParameters) -> form_result!(Self) {
+        Ok(Self { handle: new_field(parameters)?, name: None, data: None })
+    }
+
+    /// Create a new field with user data (see `new_field()`).
+    pub fn with_data(parameters: FieldParameters, data: T) -> form_result!(Self) {
+        Ok(Self { handle: new_field(parameters)?, name: None, data: Some(data) })
+    }
+
+    /// Set the name of the field used by `Form::load_values()` and `Form::save_values()`.
+    pub fn set_name<S: Into<String>>(mut self, name: S) -> Self {
+        self.name = Some(name.into());
+
+        self
+    }
+
+    /// Duplicate the field at a new location, the name and user data are not copied (see `dup_field()`).
+    pub fn dup(&self, origin: Origin) -> form_result!(Self) {
+        Ok(Self { handle: dup_field(self.handle, origin)?, name: None, data: None })
+    }
+
+    /// Create a field at a new location sharing the buffers of this field,
+    /// the name and user data are not copied (see `link_field()`).
+    pub fn link(&self, origin: Origin) -> form_result!(Self) {
+        Ok(Self { handle: link_field(self.handle, origin)?, name: None, data: None })
+    }
+
+    /// The NCurses field handle, this remains owned by the field and must not be freed.
+    pub fn handle(&self) -> FIELD {
+        self.handle
+    }
+
+    /// The name of the field.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// The user data of the field.
+    pub fn data(&self) -> Option<&T> {
+        self.data.as_ref()
+    }
+
+    /// The mutable user data of the field.
+    pub fn data_mut(&mut self) -> Option<&mut T> {
+        self.data.as_mut()
+    }
+
+    /// Set the user data of the field returning the previous user data.
+    pub fn set_data(&mut self, data: T) -> Option<T> {
+        self.data.replace(data)
+    }
+
+    /// Remove the user data of the field.
+    pub fn take_data(&mut self) -> Option<T> {
+        self.data.take()
+    }
+
+    /// The contents of the field's primary buffer (see `field_value()`).
+    pub fn value(&self, trim: bool) -> form_result!(String) {
+        field_value(self.handle, 0, trim)
+    }
+
+    /// Set the contents of the field's primary buffer (see `set_field_value()`).
+    pub fn set_value<S: Into<String>>(&self, value: S) -> form_result!(()) {
+        set_field_value(self.handle, 0, value)
+    }
+
+    /// The password of a password field, the field's buffer is cleared (see `field_secret()`).
+    pub fn secret(&self) -> form_result!(Zeroizing<String>) {
+        field_secret(self.handle)
+    }
+
+    /// Set the field type of the field (see `set_field_type()`).
+    pub fn set_field_type(&self, fieldtype: &FieldType) -> form_result!(()) {
+        set_field_type(Some(self.handle), fieldtype)
+    }
+}
+
+impl<T> Drop for Field<T> {
+    fn drop(&mut self) {
+        let _ = free_field(self.handle);
+    }
+}
@@ -0,0 +1,381 @@
+/*
+    src/form/ownedform.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{iter::FromIterator, convert::TryFrom};
+use crate::{
+    CharacterResult, GetCharacterType, NCurseswError, delwin,
+    shims::ncurses::{SCREEN, WINDOW},
+    form::{
+        FIELD, FORM, Field, FieldCompleter, FormFeedback, FormInputResult, FormKeyMap, FormPager,
+        current_field, draw_password_masks, form_handle_input, is_field_password, form_sub, form_win, free_form, new_form,
+        new_form_sp, post_form, set_form_fields, set_form_sub, set_form_win, unpost_form,
+        forminput::{invalid_fields, validate_fields},
+        ncurseswformerror::NCurseswFormError
+    }
+};
+
+/// A form that owns its fields.
+///
+/// The form keeps the contiguous array of field handles that NCurses uses
+/// for as long as the form exists, when dropped the form is unposted and
+/// freed with `free_form()` before its fields are freed. A form created by
+/// `FormBuilder` also owns its window and subwindow which are deleted last.
+///
+/// ```text
+/// let mut form = Form::new(fields)?;
+///
+/// form.set_window(window, subwindow)?;
+/// form.post()?;
+///
+/// if let Some(field) = form.current_field()? {
+///     let data = field.data();
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Form<T = ()> {
+    handle:   FORM,
+    // the null terminated field array referenced by NCurses.
+    handles:  Vec<FIELD>,
+    fields:   Vec<Field<T>>,
+    windows:  Vec<WINDOW>,
+    feedback:   Option<FormFeedback>,
+    pager:      Option<FormPager>,
+    completers: Vec<FieldCompleter>
+}
+
+impl<T> Form<T> {
+    /// Create a new form connected to the fields (see `new_form()`).
+    pub fn new(fields: Vec<Field<T>>) -> form_result!(Self) {
+        let mut handles = Self::field_handles(&fields);
+        let handle = new_form(&mut handles)?;
+
+        Ok(Self { handle, handles, fields, windows: vec![], feedback: None, pager: None, completers: vec![] })
+    }
+
+    /// Create a new form on a screen connected to the fields (see `new_form_sp()`).
+    pub fn new_sp(screen: SCREEN, fields: Vec<Field<T>>) -> form_result!(Self) {
+        let mut handles = Self::field_handles(&fields);
+        let handle = new_form_sp(screen, &mut handles)?;
+
+        Ok(Self { handle, handles, fields, windows: vec![], feedback: None, pager: None, completers: vec![] })
+    }
+
+    fn field_handles(fields: &[Field<T>]) -> Vec<FIELD> {
+        fields.iter().map(Field::handle).collect()
+    }
+
+    /// The NCurses form handle, this remains owned by the form and must not be freed.
+    pub fn handle(&self) -> FORM {
+        self.handle
+    }
+
+    /// The fields of the form.
+    pub fn fields(&self) -> &[Field<T>] {
+        &self.fields
+    }
+
+    /// The mutable fields of the form.
+    pub fn fields_mut(&mut self) -> &mut [Field<T>] {
+        &mut self.fields
+    }
+
+    /// The field at an index.
+    pub fn field(&self, index: usize) -> Option<&Field<T>> {
+        self.fields.get(index)
+    }
+
+    /// The mutable field at an index.
+    pub fn field_mut(&mut self, index: usize) -> Option<&mut Field<T>> {
+        self.fields.get_mut(index)
+    }
+
+    /// The field of the form with an NCurses field handle (i.e. the field
+    /// passed to a form hook).
+    pub fn field_by_handle(&self, handle: FIELD) -> Option<&Field<T>> {
+        self.fields.iter().find(|field| field.handle() == handle)
+    }
+
+    /// The mutable field of the form with an NCurses field handle.
+    pub fn field_by_handle_mut(&mut self, handle: FIELD) -> Option<&mut Field<T>> {
+        self.fields.iter_mut().find(|field| field.handle() == handle)
+    }
+
+    /// The field with a name (see `Field::set_name()`).
+    pub fn field_by_name(&self, name: &str) -> Option<&Field<T>> {
+        self.fields.iter().find(|field| field.name() == Some(name))
+    }
+
+    /// The mutable field with a name.
+    pub fn field_by_name_mut(&mut self, name: &str) -> Option<&mut Field<T>> {
+        self.fields.iter_mut().find(|field| field.name() == Some(name))
+    }
+
+    /// Set the values of the named fields from pairs of field names and values
+    /// (i.e. a `HashMap<String, String>`), names that do not match a field are ignored.
+    ///
+    /// ```text
+    /// form.load_values(vec![("Name", "localhost"), ("Port", "8080")])?;
+    /// ```
+    pub fn load_values<I, K, V>(&self, values: I) -> form_result!(())
+        where I: IntoIterator<Item = (K, V)>,
+              K: AsRef<str>,
+              V: Into<String>
+    {
+        for (name, value) in values {
+            if let Some(field) = self.field_by_name(name.as_ref()) {
+                field.set_value(value)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the values of the named fields as pairs of field names and values
+    /// collected into any container (i.e. a `HashMap<String, String>`), the
+    /// trailing padding of each value is removed (see `field_value()`).
+    ///
+    /// If the form is posted the current field and then every other active and
+    /// visible field is validated with `FormRequest::Validate` (which also
+    /// synchronises the current field's buffer with any editing in progress),
+    /// if any fail validation then a `NCurseswFormError::InvalidFields` is
+    /// returned with the names of all of the invalid fields (fields that have
+    /// not been edited are only validated if their `O_PASSOK` option is off).
+    /// The current field is restored afterwards, field and form hooks are
+    /// called as each field is visited.
+    ///
+    /// Password fields are not included (see `Field::secret()`).
+    ///
+    /// ```text
+    /// let values: HashMap<String, String> = form.save_values()?;
+    /// ```
+    pub fn save_values<C: FromIterator<(String, String)>>(&self) -> form_result!(C) {
+        let invalid = match invalid_fields(self.handle, &self.handles) {
+            Ok(invalid)                                => invalid,
+            Err(NCurseswFormError::NotPosted { .. }) => vec![],
+            Err(source)                                => return Err(source)
+        };
+
+        if !invalid.is_empty() {
+            let fields = invalid.into_iter().filter_map(|handle| self.field_by_handle(handle).and_then(Field::name)).map(str::to_string).collect();
+
+            return Err(NCurseswFormError::InvalidFields { func: "Form::save_values".to_string(), fields });
+        }
+
+        self.fields
+            .iter()
+            .filter(|field| !is_field_password(field.handle()))
+            .filter_map(|field| field.name().map(|name| field.value(true).map(|value| (name.to_string(), value))))
+            .collect()
+    }
+
+    /// The current field of the form (see `current_field()`).
+    pub fn current_field(&self) -> form_result!(Option<&Field<T>>) {
+        let handle = current_field(Some(self.handle))?;
+
+        Ok(self.field_by_handle(handle))
+    }
+
+    /// The mutable current field of the form.
+    pub fn current_field_mut(&mut self) -> form_result!(Option<&mut Field<T>>) {
+        let handle = current_field(Some(self.handle))?;
+
+        Ok(self.field_by_handle_mut(handle))
+    }
+
+    /// Replace the fields of the form (see `set_form_fields()`) returning the
+    /// previous fields which are no longer connected to the form.
+    pub fn set_fields(&mut self, fields: Vec<Field<T>>) -> form_result!(Vec<Field<T>>) {
+        let mut handles = Self::field_handles(&fields);
+
+        set_form_fields(self.handle, &mut handles)?;
+
+        // NCurses no longer references the previous handles so they can be released.
+        self.handles = handles;
+
+        Ok(std::mem::replace(&mut self.fields, fields))
+    }
+
+    /// Set the main and sub windows of the form (see `set_form_win()` and `set_form_sub()`).
+    pub fn set_window(&self, window: WINDOW, subwindow: WINDOW) -> form_result!(()) {
+        set_form_win(Some(self.handle), Some(window))?;
+        set_form_sub(Some(self.handle), Some(subwindow))
+    }
+
+    // set the main and sub windows of the form which are deleted when the form is dropped.
+    pub(in crate::form) fn set_owned_windows(&mut self, window: WINDOW, subwindow: WINDOW) -> form_result!(()) {
+        self.windows = vec![window, subwindow];
+
+        self.set_window(window, subwindow)
+    }
+
+    /// The main window of the form (see `form_win()`).
+    pub fn window(&self) -> form_result!(WINDOW) {
+        form_win(Some(self.handle))
+    }
+
+    /// The subwindow of the form (see `form_sub()`).
+    pub fn subwindow(&self) -> form_result!(WINDOW) {
+        form_sub(Some(self.handle))
+    }
+
+    /// Post the form (see `post_form()`).
+    pub fn post(&self) -> form_result!(()) {
+        post_form(self.handle)
+    }
+
+    /// Unpost the form (see `unpost_form()`).
+    pub fn unpost(&self) -> form_result!(()) {
+        unpost_form(self.handle)
+    }
+
+    /// Set the validation feedback of the form used by `Form::handle_input()`.
+    pub fn set_feedback(&mut self, feedback: FormFeedback) {
+        self.feedback = Some(feedback);
+    }
+
+    /// The validation feedback of the form.
+    pub fn feedback(&self) -> Option<&FormFeedback> {
+        self.feedback.as_ref()
+    }
+
+    /// Set the pager of the form used by `Form::handle_input()` to change pages.
+    pub fn set_pager(&mut self, pager: FormPager) {
+        self.pager = Some(pager);
+    }
+
+    /// The pager of the form.
+    pub fn pager(&self) -> Option<&FormPager> {
+        self.pager.as_ref()
+    }
+
+    /// Add an autocomplete dropdown to a field of the form used by `Form::handle_input()`.
+    pub fn add_completer(&mut self, completer: FieldCompleter) {
+        self.completers.push(completer);
+    }
+
+    /// Process an input event for the posted form (see `form_handle_input()`,
+    /// `FormPager::handle_input()` if the form has a pager or `FieldCompleter::handle_input()`
+    /// if the current field has an autocomplete dropdown).
+    ///
+    /// When the form is submitted each active field is validated in turn, if a
+    /// field is invalid it becomes the current field and `FormInputResult::Invalid`
+    /// is returned. Invalid fields are highlighted by the form's feedback (see
+    /// `Form::set_feedback()`) until they are valid.
+    pub fn handle_input<E>(&mut self, event: CharacterResult<E>, keymap: &FormKeyMap) -> result!(FormInputResult)
+        where E:    GetCharacterType + Copy,
+              char: TryFrom<E>
+    {
+        let current = current_field(Some(self.handle))?;
+
+        let result = match (&self.pager, self.completers.iter_mut().find(|completer| completer.field() == current)) {
+            (pager, Some(completer)) => {
+                let result = completer.handle_input(self.handle, event, keymap)?;
+
+                if let Some(pager) = pager {
+                    pager.draw(self.handle)?;
+                }
+
+                result
+            },
+            (Some(pager), None)      => pager.handle_input(self.handle, event, keymap)?,
+            (None, None)             => {
+                let result = form_handle_input(self.handle, event, keymap)?;
+
+                draw_password_masks(self.handle)?;
+
+                result
+            }
+        };
+
+        let result = match result {
+            FormInputResult::Submitted => {
+                validate_fields(self.handle, &self.handles)?.map_or(FormInputResult::Submitted, FormInputResult::Invalid)
+            },
+            result                     => result
+        };
+
+        if let Some(feedback) = self.feedback.as_mut() {
+            match result {
+                FormInputResult::Invalid(field)                          => feedback.show_invalid(field)?,
+                FormInputResult::Submitted | FormInputResult::Cancelled => feedback.clear_invalid()?,
+                // NCurses only allows an invalid field to be left once it is valid.
+                _                                                        => {
+                    if feedback.invalid_field() != Some(current_field(Some(self.handle))?) {
+                        feedback.clear_invalid()?;
+                    }
+                }
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+impl<T> Drop for Form<T> {
+    fn drop(&mut self) {
+        // the form may not be posted.
+        let _ = unpost_form(self.handle);
+        let _ = free_form(self.handle);
+
+        for window in self.windows.iter().rev() {
+            let _ = delwin(*window);
+        }
+    }
+}
+
+#[test]
+fn save_values_test() {
+    use std::collections::HashMap;
+    use crate::{Origin, Size, funcs::with_test_screen, form::{FieldOptions, FieldParameters, FieldType, field_opts_off, field_value}};
+
+    with_test_screen(|_| {
+        let field = |y, name| Field::<()>::new(FieldParameters::new(Size { lines: 1, columns: 10 }, Origin { y, x: 1 }, 0, 0)).unwrap().set_name(name);
+        let fields = vec![field(0, "first"), field(1, "second"), field(2, "third")];
+        let integer = FieldType::integer(0, 1, 100);
+
+        // fields that have not been edited are only validated without `O_PASSOK`.
+        for field in &fields {
+            field.set_field_type(&integer).unwrap();
+            field_opts_off(Some(field.handle()), FieldOptions::default().set_pass_ok(true)).unwrap();
+        }
+
+        let form = Form::new(fields).unwrap();
+
+        form.load_values(vec![("first", "abc"), ("second", "42"), ("third", "xyz")]).unwrap();
+        form.post().unwrap();
+
+        match form.save_values::<HashMap<String, String>>() {
+            Err(NCurseswFormError::InvalidFields { fields, .. }) => assert_eq!(fields, vec!["first", "third"]),
+            result                                                => panic!("unexpected result {:?}", result)
+        }
+
+        // the current field and the field types are restored.
+        assert_eq!(form.current_field().unwrap().and_then(Field::name), Some("first"));
+
+        form.load_values(vec![("first", "1"), ("third", "3")]).unwrap();
+
+        let values: HashMap<String, String> = form.save_values().unwrap();
+
+        assert_eq!(values.get("second").map(String::as_str), Some("42"));
+        assert_eq!(field_value(form.fields()[2].handle(), 0, true).unwrap(), "3");
+    });
+}
@@ -0,0 +1,303 @@
+/*
+    src/form/passwordfield.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{cell::RefCell, collections::HashMap, convert::TryFrom, slice};
+use zeroize::Zeroizing;
+use crate::{
+    NCurseswError, Origin, WINDOW, getcchar, getmaxyx, mvwaddstr, mvwin_wch,
+    funcs::char_width,
+    shims::bindings,
+    form::{
+        FIELD, FORM, FieldOptions, NCurseswFormError, current_field, field_info,
+        field_opts_off, field_opts_on, field_pad, form_page, form_sub, pos_form_cursor,
+        set_field_buffer, formpager::page_fields, ncursesw_form_error_system_error
+    }
+};
+
+thread_local! {
+    // Password fields keyed on the field handle.
+    static PASSWORD_FIELDS: RefCell<HashMap<usize, PasswordField>> = RefCell::new(HashMap::new());
+}
+
+#[derive(Copy, Clone, Debug)]
+struct PasswordField {
+    mask:     Option<char>,
+    revealed: bool
+}
+
+/// Make a field a password field.
+///
+/// The field's `FieldOptions::Public` option is turned off so that NCurses does
+/// not display its contents, if `mask` is given then `form_handle_input()`
+/// displays the mask character in place of each character of the password.
+/// The password can be temporarily revealed with `set_field_revealed()` (by
+/// default bound to Ctrl-R, see `FormAction::ToggleReveal`). The mask character
+/// must be one column wide otherwise `NCurseswFormError::BadArgument` is returned.
+///
+/// The password should be read with `field_secret()` which clears the field's
+/// buffer, password fields are not included by `Form::save_values()`.
+pub fn set_field_password(field: FIELD, mask: Option<char>) -> form_result!(()) {
+    if mask.is_some_and(|mask| char_width(mask) != 1) {
+        return Err(NCurseswFormError::BadArgument { func: "set_field_password".to_string() });
+    }
+
+    field_opts_off(Some(field), FieldOptions::default().set_public(true))?;
+
+    PASSWORD_FIELDS.with(|fields| fields.borrow_mut().insert(field as usize, PasswordField { mask, revealed: false }));
+
+    Ok(())
+}
+
+/// Is the field a password field.
+pub fn is_field_password(field: FIELD) -> bool {
+    PASSWORD_FIELDS.with(|fields| fields.borrow().contains_key(&(field as usize)))
+}
+
+/// Is the password of a password field revealed.
+pub fn is_field_revealed(field: FIELD) -> bool {
+    password_field(field).is_some_and(|password| password.revealed)
+}
+
+/// Reveal (or hide) the password of a password field by turning its
+/// `FieldOptions::Public` option on (or off).
+pub fn set_field_revealed(field: FIELD, revealed: bool) -> form_result!(()) {
+    if !is_field_password(field) {
+        return Err(NCurseswFormError::BadArgument { func: "set_field_revealed".to_string() });
+    }
+
+    if revealed {
+        field_opts_on(Some(field), FieldOptions::default().set_public(true))?;
+    } else {
+        field_opts_off(Some(field), FieldOptions::default().set_public(true))?;
+    }
+
+    PASSWORD_FIELDS.with(|fields| {
+        if let Some(password) = fields.borrow_mut().get_mut(&(field as usize)) {
+            password.revealed = revealed;
+        }
+    });
+
+    Ok(())
+}
+
+/// Returns the contents of a field's primary buffer (with the trailing padding
+/// removed) in a buffer that is zeroed when dropped, the field's buffer is then
+/// cleared. The field's buffer is copied once into the returned buffer (no
+/// other copies of the password are made).
+///
+/// If the field is the current field of a posted form then the form should
+/// be validated (see `FormRequest::Validate`) before the secret is read as
+/// NCurses only updates the field's buffer when the field is validated.
+pub fn field_secret(field: FIELD) -> form_result!(Zeroizing<String>) {
+    let mut bytes = secret_buffer(field)?;
+
+    let length = bytes.iter().rposition(|byte| *byte != b' ').map_or(0, |position| position + 1);
+    bytes.truncate(length);
+
+    let secret = match String::from_utf8(bytes.to_vec()) {
+        Ok(secret)  => Zeroizing::new(secret),
+        Err(source) => {
+            let invalid = Zeroizing::new(source.into_bytes());
+
+            Zeroizing::new(String::from_utf8_lossy(&invalid).into_owned())
+        }
+    };
+
+    // overwrite the field's buffer, NCurses pads it with blanks.
+    set_field_buffer(field, 0, &[0])?;
+
+    Ok(secret)
+}
+
+// copy a field's primary buffer into a buffer that is zeroed when dropped,
+// the buffer is allocated once so that no partial copies are left when it grows.
+fn secret_buffer(field: FIELD) -> form_result!(Zeroizing<Vec<u8>>) {
+    let ptr = unsafe { bindings::field_buffer(field, 0) };
+
+    if ptr.is_null() {
+        return Err(form_function_error!("field_buffer"));
+    }
+
+    let length = unsafe { libc::strlen(ptr) };
+    let mut buffer = Zeroizing::new(Vec::with_capacity(length));
+
+    buffer.extend_from_slice(unsafe { slice::from_raw_parts(ptr.cast::<u8>(), length) });
+
+    Ok(buffer)
+}
+
+fn password_field(field: FIELD) -> Option<PasswordField> {
+    PASSWORD_FIELDS.with(|fields| fields.borrow().get(&(field as usize)).copied())
+}
+
+/// Display the mask characters of the masked password fields on the current
+/// page of a posted form.
+///
+/// NCurses blanks a field that is not public whenever it is displayed so this
+/// should be called after each call to `form_handle_input()`, this is done by
+/// `Form::handle_input()` and `FormPager::handle_input()`.
+pub fn draw_password_masks(form: FORM) -> result!(()) {
+    if PASSWORD_FIELDS.with(|fields| fields.borrow().is_empty()) {
+        return Ok(());
+    }
+
+    let page = usize::try_from(form_page(Some(form))?)?;
+    let fields = page_fields(form)?;
+    let current = current_field(Some(form))?;
+    let mut drawn = false;
+
+    for &field in fields.get(page).map_or(&[][..], Vec::as_slice) {
+        let mask = match password_field(field) {
+            Some(PasswordField { mask: Some(mask), revealed: false }) => mask,
+            _                                                        => continue
+        };
+
+        // NCurses only updates the buffer of the current field when it is
+        // validated, the field is edited in the form's field window.
+        let length = if field == current {
+            window_password_length(field_window(form), field_pad(Some(field))?)?
+        } else {
+            buffer_password_length(field)?
+        };
+
+        draw_password_mask(form, field, mask, length)?;
+        drawn = true;
+    }
+
+    if drawn {
+        pos_form_cursor(form)?;
+    }
+
+    Ok(())
+}
+
+// the window the current field of a posted form is edited in.
+fn field_window(form: FORM) -> WINDOW {
+    unsafe { (*form).w }
+}
+
+// the number of characters of the password in a field's buffer.
+fn buffer_password_length(field: FIELD) -> result!(usize) {
+    let buffer = secret_buffer(field)?;
+    let length = buffer.iter().rposition(|byte| *byte != b' ').map_or(0, |position| position + 1);
+
+    // count the characters (not the continuation bytes) of the password.
+    Ok(length - buffer[..length].iter().filter(|byte| **byte & 0xc0 == 0x80).count())
+}
+
+// the number of characters of the password being edited in the window of the
+// current field (a pad as the field is not public), the window is read a
+// character at a time so that no copy of the password is made.
+fn window_password_length(window: WINDOW, pad: char) -> result!(usize) {
+    let size = getmaxyx(window)?;
+    let (mut characters, mut length) = (0, 0);
+
+    for y in 0..size.lines {
+        let mut x = 0;
+
+        while x < size.columns {
+            let character = char::try_from(getcchar(mvwin_wch(window, Origin { y, x })?)?.character()).unwrap_or(pad);
+
+            characters += 1;
+
+            if character != pad && character != ' ' {
+                length = characters;
+            }
+
+            x += i32::try_from(char_width(character))?;
+        }
+    }
+
+    Ok(length)
+}
+
+fn draw_password_mask(form: FORM, field: FIELD, mask: char, mut length: usize) -> result!(()) {
+    let window = form_sub(Some(form))?;
+    let parameters = field_info(field)?;
+    let size = parameters.size();
+    let columns = usize::try_from(size.columns)?;
+    let pad = field_pad(Some(field))?;
+
+    for row in 0..size.lines {
+        let masked = length.min(columns);
+        let line = format!("{}{}", mask.to_string().repeat(masked), pad.to_string().repeat(columns - masked));
+
+        mvwaddstr(window, Origin { y: parameters.origin().y + row, x: parameters.origin().x }, line)?;
+
+        length -= masked;
+    }
+
+    Ok(())
+}
+
+// forget a password field, this is called by `free_field()` as NCurses may
+// reuse the field handle for a subsequent field.
+pub(in crate::form) fn remove_field_password(field: FIELD) {
+    PASSWORD_FIELDS.with(|fields| fields.borrow_mut().remove(&(field as usize)));
+}
+
+#[test]
+fn password_field_test() {
+    use crate::{
+        Size, mvwinnstr, funcs::with_test_screen,
+        form::{
+            FormKeyMap, FormRequest, FieldParameters, field_buffer, form_handle_input, form_request,
+            free_field, free_form, new_field, new_form, post_form, unpost_form
+        },
+        CharacterResult
+    };
+
+    with_test_screen(|_| {
+        unsafe { libc::setlocale(libc::LC_CTYPE, c"C.UTF-8".as_ptr()) };
+
+        let password = new_field(FieldParameters::new(Size { lines: 1, columns: 10 }, Origin { y: 0, x: 0 }, 0, 0)).unwrap();
+        let mut fields = vec![password];
+
+        // the mask must be one column wide.
+        assert!(set_field_password(password, Some('＊')).is_err());
+
+        set_field_password(password, Some('*')).unwrap();
+
+        let form = new_form(&mut fields).unwrap();
+        let keymap = FormKeyMap::default();
+
+        post_form(form).unwrap();
+
+        for ch in "secret".chars() {
+            form_handle_input(form, CharacterResult::Character(ch), &keymap).unwrap();
+            draw_password_masks(form).unwrap();
+        }
+
+        // the mask is drawn without updating the field's buffer.
+        assert_eq!(mvwinnstr(form_sub(Some(form)).unwrap(), Origin { y: 0, x: 0 }, 10).unwrap(), "******    ");
+        assert!(field_buffer(password, 0).unwrap().iter().all(|byte| *byte as u8 == b' '));
+
+        form_request(form, FormRequest::Validate).unwrap();
+
+        assert_eq!(field_secret(password).unwrap().as_str(), "secret");
+        assert!(field_buffer(password, 0).unwrap().iter().all(|byte| *byte as u8 == b' '));
+
+        unpost_form(form).unwrap();
+        free_form(form).unwrap();
+        free_field(password).unwrap();
+    });
+}
@@ -21,7 +21,9 @@
 */
 
 use semver::{Version, Prerelease, BuildMetadata};
-use crate::shims::bindings;
+use crate::{NCurseswError, Origin, getbegyx, getparyx, wgetparent, shims::{bindings, ncurses::WINDOW}};
+#[cfg(test)]
+use crate::SCREEN;
 
 /// Return the semantic version of the NCurses library.
 pub fn ncurses_version() -> Version {
@@ -33,3 +35,48 @@ pub fn ncurses_version() -> Version {
         build: BuildMetadata::EMPTY
     }
 }
+
+// The screen origin of a window, a derived window is not moved with it's
+// parent (i.e. by `move_panel()`) so it's origin is relative to it's parent.
+pub(in crate) fn window_screen_origin(window: WINDOW) -> result!(Origin) {
+    match wgetparent(window) {
+        Some(parent) => {
+            let (parent_origin, origin) = (window_screen_origin(parent)?, getparyx(window)?);
+
+            Ok(Origin { y: parent_origin.y + origin.y, x: parent_origin.x + origin.x })
+        },
+        None         => getbegyx(window)
+    }
+}
+
+// the number of screen columns a character occupies in the current locale
+// (see `wcwidth(3)`), a non-printable character is counted as one column.
+pub(in crate) fn char_width(ch: char) -> usize {
+    usize::try_from(unsafe { bindings::wcwidth(ch as bindings::wchar_t) }).unwrap_or(1)
+}
+
+// the number of screen columns a string occupies (see `char_width()`).
+pub(in crate) fn str_width(str: &str) -> usize {
+    str.chars().map(char_width).sum()
+}
+
+// Run a test with a xterm screen on `/dev/null` as the current screen, the tests
+// using a screen are run one at a time as NCurses is not thread safe.
+#[cfg(test)]
+pub(in crate) fn with_test_screen<F: FnOnce(SCREEN)>(test: F) {
+    use std::{fs::OpenOptions, sync::Mutex};
+
+    static TEST_SCREEN: Mutex<()> = Mutex::new(());
+
+    let _lock = TEST_SCREEN.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let terminal = OpenOptions::new().read(true).write(true).open("/dev/null").unwrap();
+    std::env::set_var("TERM", "xterm");
+
+    let screen = crate::newterm(None::<String>, &terminal, &terminal).unwrap();
+
+    test(screen);
+
+    // `endwin()` fails as `/dev/null` is not a terminal.
+    let _ = crate::endwin();
+    crate::delscreen(screen);
+}
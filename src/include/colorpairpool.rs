@@ -0,0 +1,214 @@
+/*
+    src/include/colorpairpool.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+macro_rules! define_color_pair_pool {
+    ($max_capacity: expr) => {
+        use std::{rc::Rc, cell::RefCell, convert::TryFrom};
+        use crate::{SCREEN, NCurseswError, COLOR_PAIRS, COLOR_PAIRS_sp, ColorPairType, free_pair, free_pair_sp, colorpairlru::ColorPairLru};
+
+        /// A pool of reference counted color pairs.
+        ///
+        /// A color pair is allocated (see `alloc_pair()`) for each combination of
+        /// colors and is shared by all the `SharedColorPair`s of that combination,
+        /// when the last of them is dropped the color pair is released with
+        /// `free_pair()`. When the pool is full the least-recently used color pair
+        /// is evicted, a `SharedColorPair` of an evicted color pair is allocated
+        /// again the next time `SharedColorPair::color_pair()` is called (anything
+        /// already drawn with the evicted color pair will change color).
+        ///
+        /// All the color pairs of a screen should be allocated by one pool.
+        ///
+        /// ```text
+        /// let pool = ColorPairPool::new();
+        /// let warning = pool.alloc(Colors::new(Color::new(ColorPalette::Yellow), Color::new(ColorPalette::Black)))?;
+        ///
+        /// attr_set(Attributes::default(), warning.color_pair()?)?;
+        /// ```
+        #[derive(Clone, Debug)]
+        pub struct ColorPairPool {
+            pool: Rc<RefCell<ColorPairPoolState>>
+        }
+
+        #[derive(Debug)]
+        struct ColorPairPoolState {
+            screen:   Option<SCREEN>,
+            capacity: Option<usize>,
+            // the `SharedColorPair`s (excluding clones) of each combination of
+            // colors and the allocated color pairs.
+            lru:      ColorPairLru<Colors, ColorPair>
+        }
+
+        impl ColorPairPool {
+            /// Create a pool of color pairs.
+            pub fn new() -> Self {
+                Self::_from(None)
+            }
+
+            /// Create a pool of color pairs of a screen.
+            pub fn new_sp(screen: SCREEN) -> Self {
+                Self::_from(Some(screen))
+            }
+
+            fn _from(screen: Option<SCREEN>) -> Self {
+                let state = ColorPairPoolState { screen, capacity: None, lru: ColorPairLru::new() };
+
+                Self { pool: Rc::new(RefCell::new(state)) }
+            }
+
+            /// Set the maximum number of color pairs allocated by the pool, the
+            /// default is `COLOR_PAIRS() - 1` of the pool's screen (color pair 0
+            /// can not be changed).
+            /// The capacity is limited to the largest color pair number
+            /// (`i16::MAX` for a `normal::ColorPair`).
+            pub fn set_capacity(&self, capacity: usize) {
+                self.pool.borrow_mut().capacity = Some(capacity);
+            }
+
+            /// The maximum number of color pairs allocated by the pool.
+            pub fn capacity(&self) -> result!(usize) {
+                self.pool.borrow().capacity()
+            }
+
+            /// The number of color pairs currently allocated by the pool.
+            pub fn len(&self) -> usize {
+                self.pool.borrow().lru.len()
+            }
+
+            /// Returns `true` if the pool has no color pairs allocated.
+            pub fn is_empty(&self) -> bool {
+                self.pool.borrow().lru.len() == 0
+            }
+
+            /// Returns a shared color pair of the colors, allocating a color pair
+            /// if the colors do not already have one.
+            pub fn alloc(&self, colors: Colors) -> result!(SharedColorPair) {
+                let mut state = self.pool.borrow_mut();
+
+                state.color_pair(colors)?;
+                state.lru.acquire(colors);
+
+                Ok(SharedColorPair { shared: Rc::new(SharedColors { colors, pool: Rc::clone(&self.pool) }) })
+            }
+        }
+
+        impl Default for ColorPairPool {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl ColorPairPoolState {
+            fn capacity(&self) -> result!(usize) {
+                let max_capacity = usize::try_from($max_capacity)?;
+
+                match self.capacity {
+                    Some(capacity) => Ok(capacity.min(max_capacity)),
+                    None           => Ok(usize::try_from(self.screen.map_or_else(COLOR_PAIRS, COLOR_PAIRS_sp) - 1)?.min(max_capacity))
+                }
+            }
+
+            // the color pair of the colors (marking it as used), allocating it
+            // if necessary and evicting the least-recently used color pairs
+            // while the pool is full.
+            fn color_pair(&mut self, colors: Colors) -> result!(ColorPair) {
+                if let Some(color_pair) = self.lru.touch(colors) {
+                    return Ok(color_pair);
+                }
+
+                let capacity = self.capacity()?;
+
+                if capacity == 0 {
+                    return Err(NCurseswError::ColorPairLimit);
+                }
+
+                while self.lru.len() >= capacity {
+                    match self.lru.evict() {
+                        Some(color_pair) => self.free(color_pair),
+                        None             => break
+                    }
+                }
+
+                let color_pair = match self.screen {
+                    None         => alloc_pair(colors)?,
+                    Some(screen) => alloc_pair_sp(screen, colors)?
+                };
+
+                self.lru.insert(colors, color_pair);
+
+                Ok(color_pair)
+            }
+
+            // release a color pair with `free_pair()`, `alloc_pair()` returns color
+            // pair 0 for the default colors which can not be released.
+            fn free(&self, color_pair: ColorPair) {
+                if color_pair.number() == 0 {
+                    return;
+                }
+
+                let _ = match self.screen {
+                    None         => free_pair(color_pair),
+                    Some(screen) => free_pair_sp(screen, color_pair)
+                };
+            }
+
+            // a `SharedColorPair` has been dropped.
+            fn release(&mut self, colors: Colors) {
+                if let Some(color_pair) = self.lru.release(colors) {
+                    self.free(color_pair);
+                }
+            }
+        }
+
+        /// A reference counted color pair allocated by a `ColorPairPool`.
+        ///
+        /// Clones share the color pair which is released when the last clone is dropped.
+        #[derive(Clone, Debug)]
+        pub struct SharedColorPair {
+            shared: Rc<SharedColors>
+        }
+
+        #[derive(Debug)]
+        struct SharedColors {
+            colors: Colors,
+            pool:   Rc<RefCell<ColorPairPoolState>>
+        }
+
+        impl SharedColorPair {
+            /// The colors of the color pair.
+            pub fn colors(&self) -> Colors {
+                self.shared.colors
+            }
+
+            /// The color pair, marking it as the most-recently used color pair of
+            /// the pool and allocating it again if it has been evicted.
+            pub fn color_pair(&self) -> result!(ColorPair) {
+                self.shared.pool.borrow_mut().color_pair(self.shared.colors)
+            }
+        }
+
+        impl Drop for SharedColors {
+            fn drop(&mut self) {
+                self.pool.borrow_mut().release(self.colors);
+            }
+        }
+    }
+}
@@ -20,6 +20,9 @@
     IN THE SOFTWARE.
 */
 
+use std::str::FromStr;
+use crate::NCurseswError;
+
 macro_rules! define_rgb {
     ($type: ty) => {
         /// The (R)ed, (G)reen and (B)lue content of a color.
@@ -54,6 +57,181 @@ macro_rules! define_rgb {
             pub fn blue(&self) -> $type {
                 self.blue
             }
+
+            /// Create a new rgb instance from 8 bit red, green and blue content
+            /// scaled to the 0..1000 range used by NCurses.
+            pub fn from_rgb8(red: u8, green: u8, blue: u8) -> Self {
+                Self::from_unit(f64::from(red) / 255.0, f64::from(green) / 255.0, f64::from(blue) / 255.0)
+            }
+
+            /// Return the red, green and blue content scaled to 8 bits.
+            pub fn to_rgb8(&self) -> (u8, u8, u8) {
+                let (red, green, blue) = self.unit();
+                let scale = |content: f64| (content * 255.0).round() as u8;
+
+                (scale(red), scale(green), scale(blue))
+            }
+
+            /// Create a new rgb instance from a `#rrggbb` or `#rgb` hex string.
+            pub fn from_hex(hex: &str) -> result!(Self) {
+                let error = || NCurseswError::ColorParseError { color: hex.to_string() };
+
+                let digits = hex.strip_prefix('#').ok_or_else(error)?;
+
+                if !digits.chars().all(|ch| ch.is_ascii_hexdigit()) {
+                    return Err(error());
+                }
+
+                let content = |digits: &str| u8::from_str_radix(digits, 16).map_err(|_| error());
+
+                match digits.len() {
+                    6 => Ok(Self::from_rgb8(content(&digits[0..2])?, content(&digits[2..4])?, content(&digits[4..6])?)),
+                    3 => Ok(Self::from_rgb8(content(&digits[0..1])? * 17, content(&digits[1..2])? * 17, content(&digits[2..3])? * 17)),
+                    _ => Err(error())
+                }
+            }
+
+            /// Return the rgb content as a `#rrggbb` hex string.
+            pub fn to_hex(&self) -> String {
+                let (red, green, blue) = self.to_rgb8();
+
+                format!("#{:02x}{:02x}{:02x}", red, green, blue)
+            }
+
+            /// Create a new rgb instance from a hue (in degrees), saturation and
+            /// lightness (both 0.0 to 1.0).
+            pub fn from_hsl(hue: f64, saturation: f64, lightness: f64) -> Self {
+                let (saturation, lightness) = (saturation.clamp(0.0, 1.0), lightness.clamp(0.0, 1.0));
+                let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+
+                Self::from_chroma(hue, chroma, lightness - chroma / 2.0)
+            }
+
+            /// Return the hue (in degrees), saturation and lightness (both 0.0 to 1.0).
+            pub fn to_hsl(&self) -> (f64, f64, f64) {
+                let (hue, maximum, minimum) = self.hue();
+                let lightness = (maximum + minimum) / 2.0;
+                let chroma = maximum - minimum;
+
+                let saturation = if chroma == 0.0 {
+                    0.0
+                } else {
+                    chroma / (1.0 - (2.0 * lightness - 1.0).abs())
+                };
+
+                (hue, saturation, lightness)
+            }
+
+            /// Create a new rgb instance from a hue (in degrees), saturation and
+            /// value (both 0.0 to 1.0).
+            pub fn from_hsv(hue: f64, saturation: f64, value: f64) -> Self {
+                let (saturation, value) = (saturation.clamp(0.0, 1.0), value.clamp(0.0, 1.0));
+                let chroma = value * saturation;
+
+                Self::from_chroma(hue, chroma, value - chroma)
+            }
+
+            /// Return the hue (in degrees), saturation and value (both 0.0 to 1.0).
+            pub fn to_hsv(&self) -> (f64, f64, f64) {
+                let (hue, maximum, minimum) = self.hue();
+                let saturation = if maximum == 0.0 { 0.0 } else { (maximum - minimum) / maximum };
+
+                (hue, saturation, maximum)
+            }
+
+            /// Blend with another color, a `ratio` of 0.0 returns this color and
+            /// 1.0 returns the other color.
+            pub fn blend(&self, other: Self, ratio: f64) -> Self {
+                let ratio = ratio.clamp(0.0, 1.0);
+                let ((red, green, blue), (other_red, other_green, other_blue)) = (self.unit(), other.unit());
+                let mix = |content: f64, other: f64| content + (other - content) * ratio;
+
+                Self::from_unit(mix(red, other_red), mix(green, other_green), mix(blue, other_blue))
+            }
+
+            /// Increase the lightness (see `to_hsl()`) by `amount` (0.0 to 1.0).
+            pub fn lighten(&self, amount: f64) -> Self {
+                let (hue, saturation, lightness) = self.to_hsl();
+
+                Self::from_hsl(hue, saturation, lightness + amount)
+            }
+
+            /// Decrease the lightness (see `to_hsl()`) by `amount` (0.0 to 1.0).
+            pub fn darken(&self, amount: f64) -> Self {
+                self.lighten(-amount)
+            }
+
+            /// Return the relative luminance (0.0 to 1.0) as defined by WCAG 2.
+            pub fn relative_luminance(&self) -> f64 {
+                let (red, green, blue) = self.unit();
+                let linear = |content: f64| if content <= 0.03928 { content / 12.92 } else { ((content + 0.055) / 1.055).powf(2.4) };
+
+                0.2126 * linear(red) + 0.7152 * linear(green) + 0.0722 * linear(blue)
+            }
+
+            /// Return the contrast ratio (1.0 to 21.0) with another color as defined by WCAG 2.
+            pub fn contrast_ratio(&self, other: Self) -> f64 {
+                let (luminance, other_luminance) = (self.relative_luminance(), other.relative_luminance());
+
+                (luminance.max(other_luminance) + 0.05) / (luminance.min(other_luminance) + 0.05)
+            }
+
+            // create from red, green and blue content between 0.0 and 1.0.
+            fn from_unit(red: f64, green: f64, blue: f64) -> Self {
+                let scale = |content: f64| (content.clamp(0.0, 1.0) * 1000.0).round() as $type;
+
+                Self { red: scale(red), green: scale(green), blue: scale(blue) }
+            }
+
+            // the red, green and blue content between 0.0 and 1.0.
+            fn unit(&self) -> (f64, f64, f64) {
+                (f64::from(self.red) / 1000.0, f64::from(self.green) / 1000.0, f64::from(self.blue) / 1000.0)
+            }
+
+            // create from a hue, chroma and the amount added to each component.
+            fn from_chroma(hue: f64, chroma: f64, offset: f64) -> Self {
+                let hue = hue.rem_euclid(360.0) / 60.0;
+                let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+
+                let (red, green, blue) = match hue as u8 {
+                    0 => (chroma, x, 0.0),
+                    1 => (x, chroma, 0.0),
+                    2 => (0.0, chroma, x),
+                    3 => (0.0, x, chroma),
+                    4 => (x, 0.0, chroma),
+                    _ => (chroma, 0.0, x)
+                };
+
+                Self::from_unit(red + offset, green + offset, blue + offset)
+            }
+
+            // the hue (in degrees) with the maximum and minimum component.
+            fn hue(&self) -> (f64, f64, f64) {
+                let (red, green, blue) = self.unit();
+                let (maximum, minimum) = (red.max(green).max(blue), red.min(green).min(blue));
+                let chroma = maximum - minimum;
+
+                let hue = if chroma == 0.0 {
+                    0.0
+                } else if maximum == red {
+                    60.0 * ((green - blue) / chroma).rem_euclid(6.0)
+                } else if maximum == green {
+                    60.0 * ((blue - red) / chroma + 2.0)
+                } else {
+                    60.0 * ((red - green) / chroma + 4.0)
+                };
+
+                (hue, maximum, minimum)
+            }
+        }
+
+        impl FromStr for RGB {
+            type Err = NCurseswError;
+
+            /// Parse a `#rrggbb` or `#rgb` hex string (see `from_hex()`).
+            fn from_str(hex: &str) -> Result<Self, Self::Err> {
+                Self::from_hex(hex)
+            }
         }
     }
 }
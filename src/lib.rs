@@ -85,6 +85,14 @@ pub mod mouse;
 pub mod panels;
 /// NCurses API shims module
 pub mod shims;
+/// Named styles and themes module
+///
+/// A theme is a set of named styles (a foreground and background color with
+/// attributes) parsed from a simple text format, the styles are resolved to
+/// `normal` or extended attributes and color pairs which are allocated as
+/// required. The `ThemeManager` switches between themes at runtime, re-setting
+/// the background of the windows registered with it.
+pub mod theme;
 
 mod chtypet;
 mod complex;
@@ -94,6 +102,7 @@ mod attributescolorpairset;
 mod characterresult;
 mod cstring;
 mod changed;
+mod colorpairlru;
 mod cursortype;
 mod funcs;
 mod justification;
@@ -32,6 +32,7 @@ use crate::{
     menu::{
         ItemOptions, MenuOptions, MenuSpacing, MenuRequest,
         MenuSize, MenuUserPtr,
+        menuhook::remove_menu_hooks, menuselection::remove_menu_radio,
         ncurseswmenuerror::{
             NCurseswMenuError, ncursesw_menu_error_system_error,
             ncursesw_menu_error_from_rc
@@ -83,9 +84,17 @@ pub fn free_item(item: ITEM) -> menu_result!(()) {
 ///
 /// Make sure that `free_menu()` is called before `free_item()` otherwise the menu
 /// item will still be connected to the menu.
+///
+/// Any closures set with the `set_*_fn()` hook functions are also released
+/// and the menu's radio group mode (see `set_menu_multi_select()`) is forgotten.
 pub fn free_menu(menu: MENU) -> menu_result!(()) {
     match unsafe { nmenu::free_menu(menu) } {
-        E_OK => Ok(()),
+        E_OK => {
+            remove_menu_hooks(menu);
+            remove_menu_radio(menu);
+
+            Ok(())
+        },
         rc   => Err(menu_function_error_with_rc!("free_menu", rc))
     }
 }
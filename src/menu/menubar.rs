@@ -0,0 +1,465 @@
+/*
+    src/menu/menubar.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+#![allow(clippy::upper_case_acronyms)]
+
+use std::{ptr, convert::TryFrom};
+use crate::{
+    COLS, LINES, ChtypeChar, CharacterResult, GetCharacterType, KeyBinding,
+    NCurseswError, Origin, Size, delwin, derwin, getbegyx, getmaxyx, newwin, r#box,
+    shims::bindings,
+    mouse::{BUTTON1_CLICKED, BUTTON1_DOUBLE_CLICKED, mmask_t, getmouse, wenclose},
+    panels::{PANEL, del_panel, hide_panel, move_panel, new_panel, show_panel, top_panel},
+    menu::{
+        ITEM, MENU, MenuAction, MenuBarItem, MenuBarResult, MenuInputResult,
+        MenuKeyMap, MenuOptions, MenuRequest, MenuSize, NCurseswMenuError,
+        current_item, free_item, free_menu, item_index, menu_driver,
+        menu_handle_input, menu_opts_off, new_item, new_menu, post_menu,
+        scale_menu, set_current_item, set_menu_format, set_menu_mark, set_menu_sub,
+        set_menu_win, unpost_menu, menufilter::{item_at, item_origin}
+    }
+};
+
+type WINDOW = crate::shims::ncurses::WINDOW;
+
+/// A pull-down menu bar displayed on the top line of the screen whose entries
+/// open submenus in panels, submenus may themselves contain submenus which
+/// cascade to the right (or left when there is no room) of their parent.
+///
+/// The menu bar creates and owns all of the menus, items, windows and panels
+/// required and releases them when dropped.
+///
+/// Input events are processed by `handle_input()` using a `MenuKeyMap`:
+///
+/// - left/right move between the bar entries, re-opening the submenu if one was
+///   open, in a submenu left closes a cascaded submenu and right opens one.
+/// - down or `MenuAction::Select` on a bar entry opens it's submenu.
+/// - `MenuAction::Select` on a submenu entry opens it, or on a command closes
+///   all submenus and returns `MenuBarResult::Selected`.
+/// - `MenuAction::Cancel` closes one level of submenu at a time, when no submenu
+///   is open `MenuBarResult::Cancelled` is returned.
+/// - a mouse click on a bar entry opens it's submenu, a double click on a submenu
+///   entry selects it and a click outside of the menu bar closes all submenus.
+///
+/// As the menu bar uses panels `update_panels()` and `doupdate()` should be
+/// called after processing an input event to update the screen.
+///
+/// ```text
+/// let mut menu_bar = MenuBar::new(vec!(
+///     MenuBarItem::submenu("File", vec!(
+///         MenuBarItem::command("Open", OPEN),
+///         MenuBarItem::submenu("Recent", recent_files),
+///         MenuBarItem::command("Quit", QUIT)
+///     )),
+///     MenuBarItem::submenu("Edit", vec!(...))
+/// ))?;
+///
+/// let keymap = MenuKeyMap::default();
+///
+/// loop {
+///     match menu_bar.handle_input(wget_wch(window)?, &keymap)? {
+///         MenuBarResult::Selected(QUIT) => break,
+///         MenuBarResult::Selected(id)   => execute(id)?,
+///         _                             => { }
+///     }
+///
+///     update_panels();
+///     doupdate()?;
+/// }
+/// ```
+#[derive(Debug)]
+pub struct MenuBar {
+    bar:  MenuBarLevel,
+    open: Vec<usize>
+}
+
+impl MenuBar {
+    /// Create and post a menu bar on the top line of the screen.
+    pub fn new(items: Vec<MenuBarItem>) -> result!(Self) {
+        Ok(Self { bar: MenuBarLevel::new_bar(&items)?, open: vec!() })
+    }
+
+    /// The window of the menu bar.
+    pub fn window(&self) -> WINDOW {
+        self.bar.window
+    }
+
+    /// Is a submenu open.
+    pub fn is_open(&self) -> bool {
+        !self.open.is_empty()
+    }
+
+    /// Close all open submenus.
+    pub fn close(&mut self) -> result!(()) {
+        self.close_to(0)
+    }
+
+    /// Process an input event returned by the `getch()` or `get_wch()` families
+    /// of functions.
+    pub fn handle_input<T>(&mut self, event: CharacterResult<T>, keymap: &MenuKeyMap) -> result!(MenuBarResult)
+        where T:    GetCharacterType + Copy,
+              char: TryFrom<T>
+    {
+        let action = match event {
+            CharacterResult::Key(KeyBinding::MouseEvent) => return self.mouse_event(),
+            CharacterResult::Key(key)                    => keymap.key_action(key),
+            CharacterResult::Character(ch)               => char::try_from(ch).ok().and_then(|ch| keymap.character_action(ch))
+        };
+
+        let depth = self.open.len();
+
+        match action {
+            Some(MenuAction::Cancel)                                      => {
+                if depth == 0 {
+                    return Ok(MenuBarResult::Cancelled);
+                }
+
+                self.close_to(depth - 1)?;
+
+                Ok(MenuBarResult::Continue)
+            },
+            Some(MenuAction::Select)                                      => self.select(),
+            Some(MenuAction::Request(MenuRequest::DownItem)) if depth == 0 => self.select(),
+            Some(MenuAction::Request(MenuRequest::LeftItem)) if depth > 1  => {
+                self.close_to(depth - 1)?;
+
+                Ok(MenuBarResult::Continue)
+            },
+            Some(MenuAction::Request(request @ MenuRequest::LeftItem))  |
+            Some(MenuAction::Request(request @ MenuRequest::RightItem)) if depth > 0 => {
+                if request == MenuRequest::RightItem && self.focused_entry()?.is_submenu() {
+                    self.select()
+                } else {
+                    self.move_bar(request)
+                }
+            },
+            _                                                             => {
+                match menu_handle_input(self.level(depth).menu, event, keymap)? {
+                    MenuInputResult::Selected(_) => self.select(),
+                    MenuInputResult::Cancelled   => Ok(MenuBarResult::Cancelled),
+                    MenuInputResult::Unhandled   => Ok(MenuBarResult::Unhandled),
+                    MenuInputResult::Continue    => Ok(MenuBarResult::Continue)
+                }
+            }
+        }
+    }
+
+    // the level of the menu bar at `depth`, depth 0 is the bar itself.
+    fn level(&self, depth: usize) -> &MenuBarLevel {
+        self.open[..depth].iter().fold(&self.bar, |level, index| {
+            level.entries[*index].submenu().expect("MenuBar::level() : open entry is not a submenu")
+        })
+    }
+
+    // the entry of the current item of the inner most open level.
+    fn focused_entry(&self) -> result!(&MenuBarEntry) {
+        let level = self.level(self.open.len());
+
+        Ok(&level.entries[usize::try_from(item_index(current_item(level.menu)?)?)?])
+    }
+
+    // open the submenu of the current item of the inner most open level or
+    // if the current item is a command then close all submenus and return it.
+    fn select(&mut self) -> result!(MenuBarResult) {
+        let depth = self.open.len();
+        let item = current_item(self.level(depth).menu)?;
+        let index = usize::try_from(item_index(item)?)?;
+
+        if let MenuBarEntry::Command(id) = self.level(depth).entries[index] {
+            self.close_to(0)?;
+
+            return Ok(MenuBarResult::Selected(id));
+        }
+
+        let parent = self.level(depth);
+        let submenu = parent.entries[index].submenu().expect("MenuBar::select() : entry is not a submenu");
+
+        let origin = if depth == 0 {
+            let bar_origin = getbegyx(parent.window)?;
+            let item_origin = unsafe { item_origin(parent.menu, item) }.unwrap_or(bar_origin);
+
+            Origin { y: bar_origin.y + 1, x: bar_origin.x + item_origin.x }
+        } else {
+            let parent_origin = getbegyx(parent.window)?;
+            let parent_size = getmaxyx(parent.window)?;
+            let item_origin = unsafe { item_origin(parent.menu, item) }.unwrap_or(Origin { y: 0, x: 0 });
+            let size = getmaxyx(submenu.window)?;
+
+            let x = if parent_origin.x + parent_size.columns + size.columns > COLS() {
+                parent_origin.x - size.columns
+            } else {
+                parent_origin.x + parent_size.columns
+            };
+
+            Origin { y: parent_origin.y + item_origin.y, x }
+        };
+
+        submenu.open(origin)?;
+        self.open.push(index);
+
+        Ok(MenuBarResult::Continue)
+    }
+
+    // move to the previous or next bar entry, re-opening it's submenu if
+    // a submenu was open.
+    fn move_bar(&mut self, request: MenuRequest) -> result!(MenuBarResult) {
+        let was_open = self.is_open();
+
+        self.close_to(0)?;
+
+        match menu_driver(self.bar.menu, request) {
+            Ok(_) | Err(NCurseswMenuError::RequestDenied { .. }) => { },
+            Err(source)                                          => return Err(source.into())
+        }
+
+        if was_open && self.focused_entry()?.is_submenu() {
+            self.select()
+        } else {
+            Ok(MenuBarResult::Continue)
+        }
+    }
+
+    fn close_to(&mut self, depth: usize) -> result!(()) {
+        while self.open.len() > depth {
+            self.level(self.open.len()).close()?;
+            self.open.pop();
+        }
+
+        Ok(())
+    }
+
+    fn mouse_event(&mut self) -> result!(MenuBarResult) {
+        let mut event = bindings::MEVENT { id: 0, x: 0, y: 0, z: 0, bstate: 0 };
+
+        getmouse(&mut event)?;
+
+        let origin = Origin { y: event.y, x: event.x };
+
+        let depth = match (0..=self.open.len()).rev().find(|depth| wenclose(self.level(*depth).window, origin)) {
+            Some(depth) => depth,
+            None        => {
+                self.close_to(0)?;
+
+                return Ok(MenuBarResult::Unhandled);
+            }
+        };
+
+        self.close_to(depth)?;
+
+        // the event has already been read so the item is found here rather than
+        // by `menu_driver()` (which would read the event again).
+        let menu = self.level(depth).menu;
+
+        match unsafe { item_at(menu, origin) }? {
+            Some(item) if event.bstate & (BUTTON1_CLICKED | BUTTON1_DOUBLE_CLICKED) as mmask_t != 0 => {
+                set_current_item(menu, item)?;
+
+                if depth == 0 || event.bstate & BUTTON1_DOUBLE_CLICKED as mmask_t != 0 {
+                    self.select()
+                } else {
+                    Ok(MenuBarResult::Continue)
+                }
+            },
+            _                                                                                      => Ok(MenuBarResult::Unhandled)
+        }
+    }
+}
+
+// A menu of the menu bar (either the bar itself or a submenu) and the
+// resources it owns, this is also used by `popup_menu()`.
+#[derive(Debug)]
+pub(in crate::menu) struct MenuBarLevel {
+    pub(in crate::menu) menu:   MENU,
+    items:                      Vec<ITEM>,
+    pub(in crate::menu) window: WINDOW,
+    sub:                        WINDOW,
+    panel:                      PANEL,
+    entries:                    Vec<MenuBarEntry>
+}
+
+#[derive(Debug)]
+enum MenuBarEntry {
+    Command(usize),
+    Submenu(MenuBarLevel)
+}
+
+impl MenuBarEntry {
+    fn submenu(&self) -> Option<&MenuBarLevel> {
+        match self {
+            MenuBarEntry::Command(_)       => None,
+            MenuBarEntry::Submenu(submenu) => Some(submenu)
+        }
+    }
+
+    fn is_submenu(&self) -> bool {
+        self.submenu().is_some()
+    }
+}
+
+impl MenuBarLevel {
+    // the bar, a single line menu with a column per entry.
+    fn new_bar(items: &[MenuBarItem]) -> result!(Self) {
+        let mut level = Self::new(items, false)?;
+
+        set_menu_format(Some(level.menu), MenuSize { rows: 1, columns: i32::try_from(items.len())? })?;
+        set_menu_mark(Some(level.menu), " ")?;
+
+        level.window = newwin(Size { lines: 1, columns: COLS() }, Origin { y: 0, x: 0 })?;
+        level.panel = new_panel(level.window)?;
+
+        set_menu_win(Some(level.menu), Some(level.window))?;
+        post_menu(level.menu)?;
+
+        Ok(level)
+    }
+
+    // a submenu, a boxed single column menu in a hidden panel, entries that
+    // open a submenu have a description of ">".
+    pub(in crate::menu) fn new_submenu(items: &[MenuBarItem]) -> result!(Self) {
+        let mut level = Self::new(items, true)?;
+
+        let size = scale_menu(level.menu)?;
+
+        level.window = newwin(Size { lines: size.rows + 2, columns: size.columns + 2 }, Origin { y: 0, x: 0 })?;
+        level.sub = derwin(level.window, Size { lines: size.rows, columns: size.columns }, Origin { y: 1, x: 1 })?;
+        level.panel = new_panel(level.window)?;
+
+        r#box(level.window, ChtypeChar::from(0), ChtypeChar::from(0))?;
+        hide_panel(level.panel)?;
+
+        set_menu_win(Some(level.menu), Some(level.window))?;
+        set_menu_sub(Some(level.menu), Some(level.sub))?;
+
+        Ok(level)
+    }
+
+    fn new(items: &[MenuBarItem], submenu: bool) -> result!(Self) {
+        let mut level = Self {
+            menu:    ptr::null_mut(),
+            items:   Vec::with_capacity(items.len()),
+            window:  ptr::null_mut(),
+            sub:     ptr::null_mut(),
+            panel:   ptr::null_mut(),
+            entries: Vec::with_capacity(items.len())
+        };
+
+        // any resources allocated before an error are released by `drop()`.
+        for item in items {
+            let description = if submenu && matches!(item, MenuBarItem::Submenu { .. }) { ">" } else { "" };
+
+            level.items.push(new_item(item.label(), description)?);
+            level.entries.push(match item {
+                MenuBarItem::Command { id, .. }    => MenuBarEntry::Command(*id),
+                MenuBarItem::Submenu { items, .. } => MenuBarEntry::Submenu(Self::new_submenu(items)?)
+            });
+        }
+
+        level.menu = new_menu(&mut level.items)?;
+
+        if !submenu {
+            menu_opts_off(Some(level.menu), MenuOptions::default().set_show_description(true))?;
+        }
+
+        Ok(level)
+    }
+
+    pub(in crate::menu) fn open(&self, origin: Origin) -> result!(()) {
+        let size = getmaxyx(self.window)?;
+
+        // keep the submenu on the screen.
+        let origin = Origin {
+            y: origin.y.min(LINES() - size.lines).max(0),
+            x: origin.x.min(COLS() - size.columns).max(0)
+        };
+
+        move_panel(self.panel, origin)?;
+        post_menu(self.menu)?;
+        show_panel(self.panel)?;
+        top_panel(self.panel)?;
+
+        Ok(())
+    }
+
+    pub(in crate::menu) fn close(&self) -> result!(()) {
+        unpost_menu(self.menu)?;
+        hide_panel(self.panel)?;
+
+        Ok(())
+    }
+}
+
+impl Drop for MenuBarLevel {
+    fn drop(&mut self) {
+        if !self.menu.is_null() {
+            let _ = unpost_menu(self.menu);
+            let _ = free_menu(self.menu);
+        }
+
+        for item in &self.items {
+            let _ = free_item(*item);
+        }
+
+        if !self.panel.is_null() {
+            let _ = del_panel(self.panel);
+        }
+
+        if !self.sub.is_null() {
+            let _ = delwin(self.sub);
+        }
+
+        if !self.window.is_null() {
+            let _ = delwin(self.window);
+        }
+    }
+}
+
+#[test]
+fn menu_bar_mouse_test() {
+    use crate::{stdscr, nodelay, wget_wch, funcs::with_test_screen, mouse::{ALL_MOUSE_EVENTS, mousemask, ungetmouse}};
+
+    with_test_screen(|_| {
+        mousemask(ALL_MOUSE_EVENTS as mmask_t).unwrap();
+        nodelay(stdscr(), true).unwrap();
+
+        let mut menu_bar = MenuBar::new(vec!(
+            MenuBarItem::submenu("File", vec!(MenuBarItem::command("Open", 1), MenuBarItem::command("Quit", 2))),
+            MenuBarItem::submenu("Edit", vec!(MenuBarItem::command("Copy", 3)))
+        )).unwrap();
+        let keymap = MenuKeyMap::default();
+
+        let mut click = |y: i32, x: i32, bstate: i32| {
+            let mut event = bindings::MEVENT { id: 0, x, y, z: 0, bstate: bstate as mmask_t };
+
+            ungetmouse(&mut event).unwrap();
+            menu_bar.handle_input(wget_wch(stdscr()).unwrap(), &keymap).unwrap()
+        };
+
+        // click "File" on the bar then double click "Quit" in it's submenu.
+        assert_eq!(click(0, 1, BUTTON1_CLICKED), MenuBarResult::Continue);
+        assert_eq!(click(3, 3, BUTTON1_DOUBLE_CLICKED), MenuBarResult::Selected(2));
+        assert_eq!(click(5, 40, BUTTON1_CLICKED), MenuBarResult::Unhandled);
+
+        // no mouse events were left in the input queue.
+        assert!(wget_wch(stdscr()).is_err());
+        assert!(!menu_bar.is_open());
+    });
+}
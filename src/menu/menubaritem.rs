@@ -0,0 +1,49 @@
+/*
+    src/menu/menubaritem.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+/// An entry of a `MenuBar` or of one of it's submenus.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MenuBarItem {
+    /// A command, `id` is returned in `MenuBarResult::Selected` when chosen.
+    Command { label: String, id: usize },
+    /// An entry that opens a (cascading) submenu of items.
+    Submenu { label: String, items: Vec<MenuBarItem> }
+}
+
+impl MenuBarItem {
+    /// Create a command entry.
+    pub fn command<S: Into<String>>(label: S, id: usize) -> Self {
+        MenuBarItem::Command { label: label.into(), id }
+    }
+
+    /// Create a submenu entry.
+    pub fn submenu<S: Into<String>>(label: S, items: Vec<MenuBarItem>) -> Self {
+        MenuBarItem::Submenu { label: label.into(), items }
+    }
+
+    /// The label of the entry.
+    pub fn label(&self) -> &str {
+        match self {
+            MenuBarItem::Command { label, .. } | MenuBarItem::Submenu { label, .. } => label
+        }
+    }
+}
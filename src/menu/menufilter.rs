@@ -0,0 +1,380 @@
+/*
+    src/menu/menufilter.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::convert::TryFrom;
+use crate::{
+    normal, CharacterResult, GetCharacterType, NCurseswError, Origin,
+    WideChar, WideString, getmaxyx, mvwchgat, funcs::{char_width, window_screen_origin},
+    menu::{
+        ITEM, MENU, MenuAction, MenuFilterMode, MenuInputResult, MenuKeyMap,
+        MenuRequest, NCurseswMenuError,
+        current_item, item_name, item_opts, item_value, menu_back,
+        menu_fore, menu_grey, menu_handle_input, menu_items, menu_opts,
+        menu_sub, post_menu, set_current_item, set_menu_items, unpost_menu
+    }
+};
+
+/// Filters the items of a menu by a substring or fuzzy pattern, hiding the items
+/// that do not match by re-connecting the menu to the matching items with
+/// `set_menu_items()` and highlighting the matched characters of the item names.
+///
+/// The menu is re-connected to a vector of items owned by the filter, so the
+/// filter must not go out of scope until after `free_menu()` has been called.
+/// The items themselves are still owned by the caller and should be freed
+/// with `free_item()` as normal.
+///
+/// If no items match the pattern and the menu is posted then the menu is unposted
+/// until items match again. The current item is kept where it is still visible.
+///
+/// As NCurses redraws the items of a menu as the menu cursor moves the matched
+/// characters must be highlighted again (using `highlight_matches()`) after a
+/// request has been passed to `menu_driver()`, `handle_input()` does this for you.
+#[derive(Debug)]
+pub struct MenuFilter {
+    menu:        MENU,
+    items:       Vec<(ITEM, Vec<char>)>,
+    visible:     Vec<ITEM>,
+    matches:     Vec<Vec<usize>>,
+    pattern:     Vec<char>,
+    mode:        MenuFilterMode,
+    ignore_case: bool,
+    highlight:   normal::Attributes,
+    hidden:      bool,
+    unposted:    bool
+}
+
+impl MenuFilter {
+    /// Create a filter of all the items currently connected to `menu`.
+    ///
+    /// The filter is case-insensitive if the menu has the `MenuOption::IgnoreCase`
+    /// option set and highlights matched characters by underlining them.
+    pub fn new(menu: MENU, mode: MenuFilterMode) -> result!(Self) {
+        let items = menu_items(menu)?
+            .into_iter()
+            .map(|item| Ok((item, item_name(item)?.chars().collect())))
+            .collect::<Result<Vec<(ITEM, Vec<char>)>, NCurseswMenuError>>()?;
+
+        let mut filter = Self {
+            menu,
+            items,
+            visible:     vec!(),
+            matches:     vec!(),
+            pattern:     vec!(),
+            mode,
+            ignore_case: menu_opts(Some(menu)).is_ignore_case(),
+            highlight:   normal::Attributes::default() | normal::Attribute::Underline,
+            hidden:      false,
+            unposted:    false
+        };
+
+        filter.refilter()?;
+
+        Ok(filter)
+    }
+
+    /// Set if the pattern is matched case-insensitively.
+    pub fn set_ignore_case(mut self, ignore_case: bool) -> Self {
+        self.ignore_case = ignore_case;
+
+        self
+    }
+
+    /// Set the attributes added to the matched characters of an item name.
+    pub fn set_highlight(mut self, highlight: normal::Attributes) -> Self {
+        self.highlight = highlight;
+
+        self
+    }
+
+    /// The menu being filtered.
+    pub fn menu(&self) -> MENU {
+        self.menu
+    }
+
+    /// The items that currently match the pattern (in menu order).
+    pub fn visible_items(&self) -> &[ITEM] {
+        if self.hidden {
+            &[]
+        } else {
+            &self.visible
+        }
+    }
+
+    /// The current pattern.
+    pub fn pattern(&self) -> WideString {
+        WideString::from(self.pattern.iter().collect::<String>())
+    }
+
+    /// Set the pattern, returning the number of items that match.
+    pub fn set_pattern<S: Into<WideString>>(&mut self, pattern: S) -> result!(usize) {
+        let pattern: String = pattern.into().into();
+
+        self.pattern = pattern.chars().collect();
+
+        self.refilter()
+    }
+
+    /// Add a character to the end of the pattern, returning the number of items that match.
+    pub fn push(&mut self, wch: WideChar) -> result!(usize) {
+        self.pattern.push(char::try_from(wch)?);
+
+        self.refilter()
+    }
+
+    /// Remove the last character of the pattern, returning the number of items that match.
+    pub fn pop(&mut self) -> result!(usize) {
+        self.pattern.pop();
+
+        self.refilter()
+    }
+
+    /// Clear the pattern so that all items are visible.
+    pub fn clear(&mut self) -> result!(usize) {
+        self.pattern.clear();
+
+        self.refilter()
+    }
+
+    /// Process an input event as `menu_handle_input()` does except that printable
+    /// characters that are not bound in the key map are added to the filter's
+    /// pattern and `MenuRequest::BackPattern` and `MenuRequest::ClearPattern`
+    /// act on the filter's pattern rather than the menu's pattern buffer.
+    pub fn handle_input<T>(&mut self, event: CharacterResult<T>, keymap: &MenuKeyMap) -> result!(MenuInputResult)
+        where T:    GetCharacterType + Copy,
+              char: TryFrom<T>
+    {
+        let action = match event {
+            CharacterResult::Key(key)      => keymap.key_action(key),
+            CharacterResult::Character(ch) => match char::try_from(ch) {
+                Ok(ch) => {
+                    if let Some(action) = keymap.character_action(ch) {
+                        Some(action)
+                    } else if !ch.is_control() {
+                        self.pattern.push(ch);
+                        self.refilter()?;
+
+                        return Ok(MenuInputResult::Continue);
+                    } else {
+                        return Ok(MenuInputResult::Unhandled);
+                    }
+                },
+                Err(_) => return Ok(MenuInputResult::Unhandled)
+            }
+        };
+
+        let result = match action {
+            Some(MenuAction::Request(MenuRequest::BackPattern))  => {
+                self.pop()?;
+
+                MenuInputResult::Continue
+            },
+            Some(MenuAction::Request(MenuRequest::ClearPattern)) => {
+                self.clear()?;
+
+                MenuInputResult::Continue
+            },
+            Some(MenuAction::Cancel)                             => MenuInputResult::Cancelled,
+            _ if self.hidden                                     => MenuInputResult::Unhandled,
+            _                                                    => {
+                let result = menu_handle_input(self.menu, event, keymap)?;
+
+                self.highlight_matches()?;
+
+                result
+            }
+        };
+
+        Ok(result)
+    }
+
+    /// Highlight the matched characters of the visible item names in the menu's
+    /// sub-window.
+    pub fn highlight_matches(&self) -> result!(()) {
+        if self.hidden || self.pattern.is_empty() {
+            return Ok(());
+        }
+
+        let window = menu_sub(Some(self.menu))?;
+        let current = current_item(self.menu).ok();
+
+        for (item, indices) in self.visible.iter().zip(&self.matches) {
+            if let Some(origin) = unsafe { item_origin(self.menu, *item) } {
+                // the matched characters are indices into the name, a double-width
+                // character occupies two columns.
+                let widths: Vec<usize> = self.items
+                    .iter()
+                    .find(|(handle, _)| handle == item)
+                    .map_or_else(Vec::new, |(_, name)| name.iter().map(|ch| char_width(*ch)).collect());
+
+                let attrs = if Some(*item) == current || item_value(*item) {
+                    menu_fore(Some(self.menu))
+                } else if item_opts(Some(*item)).is_selectable() {
+                    menu_back(Some(self.menu))
+                } else {
+                    menu_grey(Some(self.menu))
+                } | self.highlight;
+
+                for index in indices {
+                    let column = i32::try_from(widths.iter().take(*index).sum::<usize>())?;
+                    let width = i32::try_from(widths.get(*index).copied().unwrap_or(1))?;
+
+                    mvwchgat(window, Origin { y: origin.y, x: origin.x + column }, width, attrs, attrs.color_pair())?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn refilter(&mut self) -> result!(usize) {
+        let (visible, matches): (Vec<ITEM>, Vec<Vec<usize>>) = self.items
+            .iter()
+            .filter_map(|(item, name)| self.mode.matches(&self.pattern, name, self.ignore_case).map(|indices| (*item, indices)))
+            .unzip();
+
+        self.matches = matches;
+
+        if visible.is_empty() {
+            if !self.hidden {
+                self.hidden = true;
+
+                match unpost_menu(self.menu) {
+                    Ok(())                                   => self.unposted = true,
+                    Err(NCurseswMenuError::NotPosted { .. }) => { },
+                    Err(source)                              => return Err(NCurseswError::from(source))
+                }
+            }
+        } else if visible != self.visible || self.hidden {
+            self.connect(visible)?;
+        }
+
+        self.highlight_matches()?;
+
+        Ok(self.visible_items().len())
+    }
+
+    fn connect(&mut self, mut visible: Vec<ITEM>) -> result!(()) {
+        let current = current_item(self.menu).ok();
+        let mut repost = self.unposted;
+
+        match set_menu_items(self.menu, &mut visible) {
+            Ok(())                                => { },
+            Err(NCurseswMenuError::Posted { .. }) => {
+                unpost_menu(self.menu)?;
+                set_menu_items(self.menu, &mut visible)?;
+
+                repost = true;
+            },
+            Err(source)                           => return Err(NCurseswError::from(source))
+        }
+
+        // NCurses now references the memory of `visible` (moving the vector
+        // does not move it's contents) so the previous vector can be dropped.
+        self.visible = visible;
+
+        if repost {
+            post_menu(self.menu)?;
+        }
+
+        self.hidden = false;
+        self.unposted = false;
+
+        if let Some(item) = current.filter(|item| self.visible.contains(item)) {
+            set_current_item(self.menu, item)?;
+        }
+
+        Ok(())
+    }
+}
+
+// The origin of an item's name within the menu's sub-window, this mirrors
+// the NCurses internal function `_nc_menu_cursor_pos()` with the addition of
+// the menu mark which is displayed before the item's name. Returns `None` if
+// the item is not in the displayed rows of the menu.
+pub(in crate::menu) unsafe fn item_origin(menu: MENU, item: ITEM) -> Option<Origin> {
+    let (menu, item) = (&*menu, &*item);
+
+    if item.y < menu.toprow || item.y >= menu.toprow + menu.arows {
+        None
+    } else {
+        Some(Origin {
+            y: i32::from(item.y - menu.toprow) * i32::from(menu.spc_rows),
+            x: i32::from(item.x) * i32::from(menu.spc_cols + menu.itemlen) + i32::from(menu.marklen)
+        })
+    }
+}
+
+// The displayed item of a posted menu at a screen position (i.e. of a mouse
+// event), this mirrors the hit testing `menu_driver()` does for a mouse event
+// without reading the event with `getmouse()`.
+pub(in crate::menu) unsafe fn item_at(menu: MENU, origin: Origin) -> result!(Option<ITEM>) {
+    let window = menu_sub(Some(menu))?;
+    let window_origin = window_screen_origin(window)?;
+    let size = getmaxyx(window)?;
+    let (y, x) = (origin.y - window_origin.y, origin.x - window_origin.x);
+
+    if !(0..size.lines).contains(&y) || !(0..size.columns).contains(&x) {
+        return Ok(None);
+    }
+
+    let (marklen, itemlen) = (i32::from((*menu).marklen), i32::from((*menu).itemlen));
+
+    Ok(menu_items(menu)?.into_iter().find(|item| {
+        item_origin(menu, *item).is_some_and(|origin| y == origin.y && (origin.x - marklen..origin.x - marklen + itemlen).contains(&x))
+    }))
+}
+
+#[test]
+fn menu_filter_highlight_test() {
+    use crate::{funcs::with_test_screen, mvwinch, menu::{free_item, free_menu, new_item, new_menu}};
+
+    with_test_screen(|_| {
+        // double-width characters are only measured in a UTF-8 locale.
+        unsafe { libc::setlocale(libc::LC_CTYPE, c"C.UTF-8".as_ptr()) };
+
+        let mut items = vec![new_item("日本語", "").unwrap(), new_item("abc", "").unwrap()];
+        let menu = new_menu(&mut items).unwrap();
+        let mut filter = MenuFilter::new(menu, MenuFilterMode::Substring).unwrap()
+            .set_highlight(normal::Attributes::default().set_underline(true));
+
+        post_menu(menu).unwrap();
+        filter.set_pattern("語").unwrap();
+
+        let window = menu_sub(Some(menu)).unwrap();
+        let origin = unsafe { item_origin(menu, items[0]) }.unwrap();
+        let is_underline = |x| mvwinch(window, Origin { y: origin.y, x: origin.x + x }).get_attributes().is_underline();
+
+        // "語" is the third character of the name but starts at the fifth column.
+        assert!(!is_underline(2));
+        assert!(!is_underline(3));
+        assert!(is_underline(4));
+        assert!(is_underline(5));
+
+        unpost_menu(menu).unwrap();
+        free_menu(menu).unwrap();
+        drop(filter);
+
+        for item in items {
+            free_item(item).unwrap();
+        }
+    });
+}
@@ -0,0 +1,77 @@
+/*
+    src/menu/menufiltermode.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+/// How a `MenuFilter` matches its pattern against the item names.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MenuFilterMode {
+    /// The pattern must appear as a contiguous substring of the item name.
+    Substring,
+    /// The characters of the pattern must appear in order in the item name
+    /// but not necessarily next to each other (i.e. "fb" matches "FooBar").
+    Fuzzy
+}
+
+impl MenuFilterMode {
+    /// Match `pattern` against `name` returning the character indices within
+    /// `name` of the matched characters, or `None` if there is no match.
+    pub(in crate::menu) fn matches(self, pattern: &[char], name: &[char], ignore_case: bool) -> Option<Vec<usize>> {
+        let eq = |a: char, b: char| if ignore_case {
+            a.to_lowercase().eq(b.to_lowercase())
+        } else {
+            a == b
+        };
+
+        match self {
+            MenuFilterMode::Substring => {
+                if pattern.len() > name.len() {
+                    return None;
+                }
+
+                (0..=name.len() - pattern.len())
+                    .find(|start| pattern.iter().zip(&name[*start..]).all(|(p, n)| eq(*p, *n)))
+                    .map(|start| (start..start + pattern.len()).collect())
+            },
+            MenuFilterMode::Fuzzy     => {
+                let mut indices = Vec::with_capacity(pattern.len());
+                let mut name = name.iter().enumerate();
+
+                for p in pattern {
+                    indices.push(name.find(|(_, n)| eq(*p, **n))?.0);
+                }
+
+                Some(indices)
+            }
+        }
+    }
+}
+
+#[test]
+fn menu_filter_mode_test() {
+    let name: Vec<char> = "Save As…".chars().collect();
+
+    assert_eq!(MenuFilterMode::Substring.matches(&['v', 'e'], &name, false), Some(vec![2, 3]));
+    assert_eq!(MenuFilterMode::Substring.matches(&['a', 's'], &name, false), None);
+    assert_eq!(MenuFilterMode::Substring.matches(&['A', 'S'], &name, true), Some(vec![5, 6]));
+    assert_eq!(MenuFilterMode::Fuzzy.matches(&['s', 'a', '…'], &name, true), Some(vec![0, 1, 7]));
+    assert_eq!(MenuFilterMode::Fuzzy.matches(&['x'], &name, true), None);
+    assert_eq!(MenuFilterMode::Fuzzy.matches(&[], &name, false), Some(vec![]));
+}
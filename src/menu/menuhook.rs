@@ -0,0 +1,252 @@
+/*
+    src/menu/menuhook.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{cell::RefCell, collections::{HashMap, HashSet}};
+use crate::menu::{
+    MENU, NCurseswMenuError,
+    set_item_init, set_item_term, set_menu_init, set_menu_term
+};
+
+type MenuHookFn = Box<dyn FnMut(MENU)>;
+
+thread_local! {
+    // Rust closures registered as menu hooks, keyed on the menu handle and
+    // the hook type. NCurses calls the `extern "C"` trampolines below which
+    // then dispatch to the closure registered for the menu being processed.
+    //
+    // NCurses is not thread-safe so the hooks are held per thread, this also
+    // means that a closure does not have to be `Send` (a closure will normally
+    // want to capture a `WINDOW` which is a raw pointer).
+    static MENU_HOOKS: RefCell<HashMap<(usize, MenuHookType), MenuHookFn>> = RefCell::new(HashMap::new());
+
+    // The hooks that are being called, a hook is removed from this set when it
+    // is replaced or removed while it is being called so that the closure is
+    // not restored once the call returns.
+    static DISPATCHING_MENU_HOOKS: RefCell<HashSet<(usize, MenuHookType)>> = RefCell::new(HashSet::new());
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum MenuHookType {
+    MenuInit,
+    MenuTerm,
+    ItemInit,
+    ItemTerm
+}
+
+/// Sets a closure to be called at menu-post time and just after the top row on the
+/// menu changes once it is posted.
+///
+/// This is the closure equivalent of `set_menu_init()`, the closure is held by the
+/// crate until it is replaced, `clear_menu_hooks()` is called or the menu is freed
+/// with `free_menu()`. Unlike `set_menu_init()` a menu must be specified.
+pub fn set_menu_init_fn<F>(menu: MENU, hook: F) -> menu_result!(())
+    where F: FnMut(MENU) + 'static
+{
+    insert_menu_hook(menu, MenuHookType::MenuInit, Box::new(hook));
+
+    if let Err(source) = set_menu_init(Some(menu), Some(menu_init_trampoline)) {
+        remove_menu_hook(menu, MenuHookType::MenuInit);
+
+        return Err(source);
+    }
+
+    Ok(())
+}
+
+/// Sets a closure to be called at menu-unpost time and just before the top row
+/// on the menu changes once it is posted.
+///
+/// This is the closure equivalent of `set_menu_term()`, see `set_menu_init_fn()`
+/// for details of how long the closure is held.
+pub fn set_menu_term_fn<F>(menu: MENU, hook: F) -> menu_result!(())
+    where F: FnMut(MENU) + 'static
+{
+    insert_menu_hook(menu, MenuHookType::MenuTerm, Box::new(hook));
+
+    if let Err(source) = set_menu_term(Some(menu), Some(menu_term_trampoline)) {
+        remove_menu_hook(menu, MenuHookType::MenuTerm);
+
+        return Err(source);
+    }
+
+    Ok(())
+}
+
+/// Sets a closure to be called at menu-post time and each time the selected
+/// item changes (after the change).
+///
+/// This is the closure equivalent of `set_item_init()`, see `set_menu_init_fn()`
+/// for details of how long the closure is held.
+pub fn set_item_init_fn<F>(menu: MENU, hook: F) -> menu_result!(())
+    where F: FnMut(MENU) + 'static
+{
+    insert_menu_hook(menu, MenuHookType::ItemInit, Box::new(hook));
+
+    if let Err(source) = set_item_init(Some(menu), Some(item_init_trampoline)) {
+        remove_menu_hook(menu, MenuHookType::ItemInit);
+
+        return Err(source);
+    }
+
+    Ok(())
+}
+
+/// Sets a closure to be called at menu-unpost time and each time the selected
+/// item changes (before the change).
+///
+/// This is the closure equivalent of `set_item_term()`, see `set_menu_init_fn()`
+/// for details of how long the closure is held.
+pub fn set_item_term_fn<F>(menu: MENU, hook: F) -> menu_result!(())
+    where F: FnMut(MENU) + 'static
+{
+    insert_menu_hook(menu, MenuHookType::ItemTerm, Box::new(hook));
+
+    if let Err(source) = set_item_term(Some(menu), Some(item_term_trampoline)) {
+        remove_menu_hook(menu, MenuHookType::ItemTerm);
+
+        return Err(source);
+    }
+
+    Ok(())
+}
+
+/// Removes any closures set with `set_menu_init_fn()`, `set_menu_term_fn()`,
+/// `set_item_init_fn()` and `set_item_term_fn()` from the menu and resets
+/// the menu's hooks.
+pub fn clear_menu_hooks(menu: MENU) -> menu_result!(()) {
+    set_menu_init(Some(menu), None)?;
+    set_menu_term(Some(menu), None)?;
+    set_item_init(Some(menu), None)?;
+    set_item_term(Some(menu), None)?;
+
+    remove_menu_hooks(menu);
+
+    Ok(())
+}
+
+// drop all closures held for the menu, this is called by `free_menu()` as
+// NCurses may reuse the menu handle for a subsequent menu.
+pub(in crate::menu) fn remove_menu_hooks(menu: MENU) {
+    MENU_HOOKS.with(|hooks| hooks.borrow_mut().retain(|(handle, _), _| *handle != menu as usize));
+    DISPATCHING_MENU_HOOKS.with(|keys| keys.borrow_mut().retain(|(handle, _)| *handle != menu as usize));
+}
+
+fn insert_menu_hook(menu: MENU, hook_type: MenuHookType, hook: MenuHookFn) {
+    MENU_HOOKS.with(|hooks| hooks.borrow_mut().insert((menu as usize, hook_type), hook));
+    DISPATCHING_MENU_HOOKS.with(|keys| keys.borrow_mut().remove(&(menu as usize, hook_type)));
+}
+
+fn remove_menu_hook(menu: MENU, hook_type: MenuHookType) {
+    MENU_HOOKS.with(|hooks| hooks.borrow_mut().remove(&(menu as usize, hook_type)));
+    DISPATCHING_MENU_HOOKS.with(|keys| keys.borrow_mut().remove(&(menu as usize, hook_type)));
+}
+
+fn dispatch_menu_hook(menu: MENU, hook_type: MenuHookType) {
+    let key = (menu as usize, hook_type);
+
+    // take the closure out of the map while it is being called so that the
+    // closure itself can call the menu functions that may trigger a hook.
+    if let Some(mut hook) = MENU_HOOKS.with(|hooks| hooks.borrow_mut().remove(&key)) {
+        DISPATCHING_MENU_HOOKS.with(|keys| keys.borrow_mut().insert(key));
+
+        hook(menu);
+
+        // restore the closure unless it was replaced or removed (by the
+        // closure itself clearing the hooks or freeing the menu) while it
+        // was being called.
+        if DISPATCHING_MENU_HOOKS.with(|keys| keys.borrow_mut().remove(&key)) {
+            MENU_HOOKS.with(|hooks| hooks.borrow_mut().insert(key, hook));
+        }
+    }
+}
+
+extern "C" fn menu_init_trampoline(menu: MENU) {
+    dispatch_menu_hook(menu, MenuHookType::MenuInit);
+}
+
+extern "C" fn menu_term_trampoline(menu: MENU) {
+    dispatch_menu_hook(menu, MenuHookType::MenuTerm);
+}
+
+extern "C" fn item_init_trampoline(menu: MENU) {
+    dispatch_menu_hook(menu, MenuHookType::ItemInit);
+}
+
+extern "C" fn item_term_trampoline(menu: MENU) {
+    dispatch_menu_hook(menu, MenuHookType::ItemTerm);
+}
+
+#[test]
+fn menu_hook_test() {
+    use std::{rc::Rc, cell::Cell};
+    use crate::{funcs::with_test_screen, menu::{MenuRequest, free_item, free_menu, menu_driver, new_item, new_menu, post_menu, unpost_menu}};
+
+    with_test_screen(|_| {
+        let mut items = vec![new_item("One", "").unwrap(), new_item("Two", "").unwrap()];
+        let menu = new_menu(&mut items).unwrap();
+        let calls = Rc::new(Cell::new(0));
+        let is_hooked = |hook_type| MENU_HOOKS.with(|hooks| hooks.borrow().contains_key(&(menu as usize, hook_type)));
+
+        // a closure that clears the hooks is not restored.
+        let cleared = Rc::clone(&calls);
+        set_item_init_fn(menu, move |menu| {
+            cleared.set(cleared.get() + 1);
+
+            clear_menu_hooks(menu).unwrap();
+        }).unwrap();
+
+        post_menu(menu).unwrap();
+
+        assert_eq!(calls.get(), 1);
+        assert!(!is_hooked(MenuHookType::ItemInit));
+
+        // a closure that replaces itself keeps the replacement.
+        set_menu_init_fn(menu, {
+            let calls = Rc::clone(&calls);
+
+            move |menu| {
+                let calls = Rc::clone(&calls);
+
+                set_menu_init_fn(menu, move |_| calls.set(calls.get() + 10)).unwrap();
+            }
+        }).unwrap();
+
+        unpost_menu(menu).unwrap();
+        post_menu(menu).unwrap();
+        menu_driver(menu, MenuRequest::DownItem).unwrap();
+
+        assert_eq!(calls.get(), 1);
+        assert!(is_hooked(MenuHookType::MenuInit));
+
+        unpost_menu(menu).unwrap();
+        post_menu(menu).unwrap();
+
+        assert_eq!(calls.get(), 11);
+
+        unpost_menu(menu).unwrap();
+        free_menu(menu).unwrap();
+
+        for item in items {
+            free_item(item).unwrap();
+        }
+    });
+}
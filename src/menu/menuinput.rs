@@ -0,0 +1,129 @@
+/*
+    src/menu/menuinput.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::convert::TryFrom;
+use crate::{
+    CharacterResult, GetCharacterType, KeyBinding,
+    shims::{
+        nmenu,
+        constants::{E_OK, E_NO_MATCH, E_REQUEST_DENIED, E_UNKNOWN_COMMAND, E_NOT_SELECTABLE}
+    },
+    menu::{
+        MENU, MenuAction, MenuInputResult, MenuKeyMap, MenuRequest, current_item,
+        menuselection::menu_item_toggled,
+        ncurseswmenuerror::{NCurseswMenuError, ncursesw_menu_error_from_rc}
+    }
+};
+
+/// Process an input event returned by the `getch()` or `get_wch()` families of
+/// functions for a posted menu using the given key map.
+///
+/// Keys and characters bound in `keymap` are actioned, a `KeyBinding::MouseEvent`
+/// is passed to `menu_driver()` so that a click positions the menu cursor on an
+/// item and a double click selects it, and any other printable character is
+/// added to the menu's pattern buffer (unless pattern matching has been turned
+/// off in the key map).
+///
+/// Requests that NCurses denies (for example moving up when on the first item)
+/// or patterns that do not match are returned as `MenuInputResult::Continue`.
+///
+/// If the menu is in radio group mode (see `set_menu_multi_select()`) then toggling
+/// an item selects it and deselects all other items.
+///
+/// ```text
+/// let keymap = MenuKeyMap::default().bind_key(KeyBinding::FunctionKey(1), MenuAction::Cancel);
+///
+/// loop {
+///     match menu_handle_input(menu, wget_wch(window)?, &keymap)? {
+///         MenuInputResult::Selected(item) => break Some(item),
+///         MenuInputResult::Cancelled      => break None,
+///         _                               => wrefresh(window)?
+///     }
+/// }
+/// ```
+pub fn menu_handle_input<T>(menu: MENU, event: CharacterResult<T>, keymap: &MenuKeyMap) -> menu_result!(MenuInputResult)
+    where T:    GetCharacterType,
+          char: TryFrom<T>
+{
+    match event {
+        CharacterResult::Key(KeyBinding::MouseEvent) => menu_mouse_event(menu),
+        CharacterResult::Key(key)                    => {
+            keymap.key_action(key).map_or(Ok(MenuInputResult::Unhandled), |action| menu_action(menu, action))
+        },
+        CharacterResult::Character(ch)               => {
+            let ch = match char::try_from(ch) {
+                Ok(ch) => ch,
+                Err(_) => return Ok(MenuInputResult::Unhandled)
+            };
+
+            if let Some(action) = keymap.character_action(ch) {
+                menu_action(menu, action)
+            } else if keymap.is_pattern_matching() && is_pattern_character(ch) {
+                menu_request(menu, MenuRequest::Navigate(ch))
+            } else {
+                Ok(MenuInputResult::Unhandled)
+            }
+        }
+    }
+}
+
+// NCurses only accepts printable characters in the single byte range as
+// pattern characters.
+fn is_pattern_character(ch: char) -> bool {
+    u32::from(ch) < 256 && !ch.is_control()
+}
+
+fn menu_action(menu: MENU, action: MenuAction) -> menu_result!(MenuInputResult) {
+    match action {
+        MenuAction::Request(request) => menu_request(menu, request),
+        MenuAction::Select           => Ok(MenuInputResult::Selected(current_item(menu)?)),
+        MenuAction::Cancel           => Ok(MenuInputResult::Cancelled)
+    }
+}
+
+fn menu_request(menu: MENU, request: MenuRequest) -> menu_result!(MenuInputResult) {
+    match unsafe { nmenu::menu_driver(menu, request.value()?) } {
+        E_OK if request == MenuRequest::ToggleItem              => {
+            menu_item_toggled(menu)?;
+
+            Ok(MenuInputResult::Continue)
+        },
+        E_OK | E_REQUEST_DENIED | E_NO_MATCH | E_NOT_SELECTABLE => Ok(MenuInputResult::Continue),
+        rc                                                      => Err(menu_function_error_with_rc!("menu_driver", rc))
+    }
+}
+
+// `menu_driver()` returns `E_UNKNOWN_COMMAND` when an item is double clicked
+// (after toggling the item) and `E_REQUEST_DENIED` when the click was outside
+// of the menu, so the mouse event is passed to the driver directly.
+fn menu_mouse_event(menu: MENU) -> menu_result!(MenuInputResult) {
+    match unsafe { nmenu::menu_driver(menu, MenuRequest::Mouse.value()?) } {
+        E_OK              => Ok(MenuInputResult::Continue),
+        E_UNKNOWN_COMMAND => {
+            menu_item_toggled(menu)?;
+
+            Ok(MenuInputResult::Selected(current_item(menu)?))
+        },
+        E_REQUEST_DENIED  => Ok(MenuInputResult::Unhandled),
+        rc                => Err(menu_function_error_with_rc!("menu_driver", rc))
+    }
+}
@@ -0,0 +1,37 @@
+/*
+    src/menu/menuinputresult.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use crate::menu::ITEM;
+
+/// The result of passing an input event to `menu_handle_input()`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MenuInputResult {
+    /// The item was selected (by a key bound to `MenuAction::Select`
+    /// or a mouse double click on an item).
+    Selected(ITEM),
+    /// The menu was cancelled.
+    Cancelled,
+    /// The event was not handled by the menu.
+    Unhandled,
+    /// The event was handled by the menu, continue processing input.
+    Continue
+}
@@ -0,0 +1,125 @@
+/*
+    src/menu/menukeymap.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::collections::HashMap;
+use crate::{KeyBinding, menu::{MenuAction, MenuRequest}};
+
+/// Key map used by `menu_handle_input()` to translate keys and characters
+/// into `MenuAction`'s.
+///
+/// The default key map binds the arrow keys, page up/down, home/end, tab and
+/// back-tab to navigation requests, backspace to `MenuRequest::BackPattern`,
+/// space to `MenuRequest::ToggleItem`, enter to `MenuAction::Select` and
+/// escape to `MenuAction::Cancel`. Any other printable character is passed
+/// to the menu's pattern buffer unless pattern matching has been turned off.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MenuKeyMap {
+    keys:             HashMap<KeyBinding, MenuAction>,
+    characters:       HashMap<char, MenuAction>,
+    pattern_matching: bool
+}
+
+impl MenuKeyMap {
+    /// An empty key map with pattern matching turned on.
+    pub fn new() -> Self {
+        Self { keys: HashMap::new(), characters: HashMap::new(), pattern_matching: true }
+    }
+
+    /// Bind a key to an action, replacing any existing binding.
+    pub fn bind_key(mut self, key: KeyBinding, action: MenuAction) -> Self {
+        self.keys.insert(key, action);
+
+        self
+    }
+
+    /// Bind a character to an action, replacing any existing binding.
+    pub fn bind_character(mut self, ch: char, action: MenuAction) -> Self {
+        self.characters.insert(ch, action);
+
+        self
+    }
+
+    /// Remove the binding of a key.
+    pub fn unbind_key(mut self, key: KeyBinding) -> Self {
+        self.keys.remove(&key);
+
+        self
+    }
+
+    /// Remove the binding of a character.
+    pub fn unbind_character(mut self, ch: char) -> Self {
+        self.characters.remove(&ch);
+
+        self
+    }
+
+    /// Set if unbound printable characters are passed to the menu's pattern buffer.
+    pub fn set_pattern_matching(mut self, pattern_matching: bool) -> Self {
+        self.pattern_matching = pattern_matching;
+
+        self
+    }
+
+    /// Returns the action bound to a key.
+    pub fn key_action(&self, key: KeyBinding) -> Option<MenuAction> {
+        self.keys.get(&key).copied()
+    }
+
+    /// Returns the action bound to a character.
+    pub fn character_action(&self, ch: char) -> Option<MenuAction> {
+        self.characters.get(&ch).copied()
+    }
+
+    /// Are unbound printable characters passed to the menu's pattern buffer.
+    pub fn is_pattern_matching(&self) -> bool {
+        self.pattern_matching
+    }
+}
+
+impl Default for MenuKeyMap {
+    fn default() -> Self {
+        Self::new()
+            .bind_key(KeyBinding::UpArrow, MenuAction::Request(MenuRequest::UpItem))
+            .bind_key(KeyBinding::DownArrow, MenuAction::Request(MenuRequest::DownItem))
+            .bind_key(KeyBinding::LeftArrow, MenuAction::Request(MenuRequest::LeftItem))
+            .bind_key(KeyBinding::RightArrow, MenuAction::Request(MenuRequest::RightItem))
+            .bind_key(KeyBinding::PreviousPage, MenuAction::Request(MenuRequest::ScrollUpPage))
+            .bind_key(KeyBinding::NextPage, MenuAction::Request(MenuRequest::ScrollDownPage))
+            .bind_key(KeyBinding::Home, MenuAction::Request(MenuRequest::FirstItem))
+            .bind_key(KeyBinding::End, MenuAction::Request(MenuRequest::LastItem))
+            .bind_key(KeyBinding::BackTab, MenuAction::Request(MenuRequest::PreviousItem))
+            .bind_key(KeyBinding::Backspace, MenuAction::Request(MenuRequest::BackPattern))
+            .bind_key(KeyBinding::Enter, MenuAction::Select)
+            .bind_key(KeyBinding::Cancel, MenuAction::Cancel)
+            .bind_key(KeyBinding::Exit, MenuAction::Cancel)
+            .bind_character('\t', MenuAction::Request(MenuRequest::NextItem))
+            .bind_character(' ', MenuAction::Request(MenuRequest::ToggleItem))
+            .bind_character('\x08', MenuAction::Request(MenuRequest::BackPattern))
+            .bind_character('\x7f', MenuAction::Request(MenuRequest::BackPattern))
+            .bind_character('\x0e', MenuAction::Request(MenuRequest::NextMatch))     // Ctrl-N
+            .bind_character('\x10', MenuAction::Request(MenuRequest::PreviousMatch)) // Ctrl-P
+            .bind_character('\x15', MenuAction::Request(MenuRequest::ClearPattern))  // Ctrl-U
+            .bind_character('\n', MenuAction::Select)
+            .bind_character('\r', MenuAction::Select)
+            .bind_character('\x1b', MenuAction::Cancel)
+    }
+}
@@ -0,0 +1,192 @@
+/*
+    src/menu/menuselection.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{cell::RefCell, collections::HashSet, convert::TryFrom};
+use crate::menu::{
+    ITEM, MENU, MenuOptions, NCurseswMenuError,
+    current_item, item_index, item_opts, item_value, menu_items,
+    menu_opts_off, set_item_value, set_menu_mark
+};
+
+/// The menu mark used by `set_menu_multi_select()` for a check box menu.
+pub const MENU_CHECKBOX_MARK: &str = "[x] ";
+/// The menu mark used by `set_menu_multi_select()` for a radio group menu.
+pub const MENU_RADIO_MARK: &str = "(*) ";
+
+thread_local! {
+    // Menus that are in radio group mode (at most one item selected), keyed on the menu handle.
+    static RADIO_MENUS: RefCell<HashSet<usize>> = RefCell::new(HashSet::new());
+}
+
+/// Make a menu multi-valued by turning off `MenuOption::OneValue` and setting
+/// the menu mark to `MENU_CHECKBOX_MARK`, or if `radio` is `true` setting the
+/// menu mark to `MENU_RADIO_MARK` and only allowing one item to be selected
+/// at a time.
+///
+/// This must be called before the menu is posted and while the menu's mark is
+/// still the default (see `set_menu_mark()`).
+///
+/// In radio group mode `menu_handle_input()` and `menu_select_item()` deselect
+/// all other items when an item is selected, if `menu_driver()` is called directly
+/// then `MenuRequest::ToggleItem` will not deselect the other items.
+pub fn set_menu_multi_select(menu: MENU, radio: bool) -> menu_result!(()) {
+    menu_opts_off(Some(menu), MenuOptions::default().set_one_value(true))?;
+    set_menu_mark(Some(menu), if radio { MENU_RADIO_MARK } else { MENU_CHECKBOX_MARK })?;
+
+    RADIO_MENUS.with(|menus| if radio {
+        menus.borrow_mut().insert(menu as usize)
+    } else {
+        menus.borrow_mut().remove(&(menu as usize))
+    });
+
+    Ok(())
+}
+
+/// Is the menu in radio group mode (see `set_menu_multi_select()`).
+pub fn is_menu_radio(menu: MENU) -> bool {
+    RADIO_MENUS.with(|menus| menus.borrow().contains(&(menu as usize)))
+}
+
+/// Returns the selected items of a multi-valued menu in menu order.
+pub fn menu_selected_items(menu: MENU) -> menu_result!(Vec<ITEM>) {
+    Ok(menu_items(menu)?.into_iter().filter(|item| item_value(*item)).collect())
+}
+
+/// Returns the values corresponding to the selected items of a multi-valued
+/// menu, `values` is indexed by the item's index (see `item_index()`).
+///
+/// ```text
+/// let colors = [Color::Red, Color::Green, Color::Blue];
+///
+/// for color in menu_selected_values(menu, &colors)? { ... }
+/// ```
+pub fn menu_selected_values<T>(menu: MENU, values: &[T]) -> menu_result!(Vec<&T>) {
+    menu_selected_items(menu)?
+        .into_iter()
+        .map(|item| {
+            let index = usize::try_from(item_index(item)?)?;
+
+            values.get(index).ok_or(NCurseswMenuError::BadArgument { func: "menu_selected_values".to_string() })
+        })
+        .collect()
+}
+
+/// Select or deselect an item of a multi-valued menu, if the menu is in
+/// radio group mode and the item is being selected then all other items
+/// are deselected.
+pub fn menu_select_item(menu: MENU, item: ITEM, value: bool) -> menu_result!(()) {
+    if value && is_menu_radio(menu) {
+        for other in menu_items(menu)?.into_iter().filter(|other| *other != item && item_value(*other)) {
+            set_item_value(other, false)?;
+        }
+    }
+
+    set_item_value(item, value)
+}
+
+/// Select all selectable items of a multi-valued menu.
+///
+/// A `NCurseswMenuError::RequestDenied` is returned if the menu is in radio group mode.
+pub fn menu_select_all(menu: MENU) -> menu_result!(()) {
+    if is_menu_radio(menu) {
+        return Err(NCurseswMenuError::RequestDenied { func: "menu_select_all".to_string() });
+    }
+
+    for item in menu_items(menu)?.into_iter().filter(|item| item_opts(Some(*item)).is_selectable()) {
+        set_item_value(item, true)?;
+    }
+
+    Ok(())
+}
+
+/// Deselect all items of a multi-valued menu.
+pub fn menu_clear_selection(menu: MENU) -> menu_result!(()) {
+    for item in menu_items(menu)?.into_iter().filter(|item| item_value(*item)) {
+        set_item_value(item, false)?;
+    }
+
+    Ok(())
+}
+
+// called after the current item has been toggled by `menu_driver()`, in radio
+// group mode the current item is always selected and all others deselected.
+pub(in crate::menu) fn menu_item_toggled(menu: MENU) -> menu_result!(()) {
+    if is_menu_radio(menu) {
+        menu_select_item(menu, current_item(menu)?, true)?;
+    }
+
+    Ok(())
+}
+
+// forget the radio group mode of the menu, this is called by `free_menu()`
+// as NCurses may reuse the menu handle for a subsequent menu.
+pub(in crate::menu) fn remove_menu_radio(menu: MENU) {
+    RADIO_MENUS.with(|menus| menus.borrow_mut().remove(&(menu as usize)));
+}
+
+#[test]
+fn menu_selection_test() {
+    use crate::{funcs::with_test_screen, menu::{free_item, free_menu, new_item, new_menu}};
+
+    with_test_screen(|_| {
+        let mut items = vec![new_item("One", "").unwrap(), new_item("Two", "").unwrap(), new_item("Three", "").unwrap()];
+        let menu = new_menu(&mut items).unwrap();
+        let values = [1, 2, 3];
+
+        set_menu_multi_select(menu, false).unwrap();
+        assert!(!is_menu_radio(menu));
+
+        menu_select_item(menu, items[0], true).unwrap();
+        menu_select_item(menu, items[2], true).unwrap();
+        assert_eq!(menu_selected_items(menu).unwrap(), vec![items[0], items[2]]);
+        assert_eq!(menu_selected_values(menu, &values).unwrap(), vec![&1, &3]);
+
+        menu_select_item(menu, items[0], false).unwrap();
+        assert_eq!(menu_selected_items(menu).unwrap(), vec![items[2]]);
+
+        menu_select_all(menu).unwrap();
+        assert_eq!(menu_selected_items(menu).unwrap(), items);
+
+        menu_clear_selection(menu).unwrap();
+        assert!(menu_selected_items(menu).unwrap().is_empty());
+
+        free_menu(menu).unwrap();
+
+        // in radio group mode selecting an item deselects all other items.
+        let menu = new_menu(&mut items).unwrap();
+
+        set_menu_multi_select(menu, true).unwrap();
+        assert!(is_menu_radio(menu));
+
+        menu_select_item(menu, items[0], true).unwrap();
+        menu_select_item(menu, items[1], true).unwrap();
+        assert_eq!(menu_selected_items(menu).unwrap(), vec![items[1]]);
+        assert!(menu_select_all(menu).is_err());
+
+        free_menu(menu).unwrap();
+        assert!(!is_menu_radio(menu));
+
+        for item in items {
+            free_item(item).unwrap();
+        }
+    });
+}
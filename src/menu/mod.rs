@@ -23,15 +23,30 @@
 mod funcs;
 mod itemoption;
 mod itemoptions;
+mod menuaction;
+mod menubar;
+mod menubaritem;
+mod menubarresult;
+mod menufilter;
+mod menufiltermode;
+mod menuhook;
+mod menuinput;
+mod menuinputresult;
+mod menukeymap;
 mod menuoption;
 mod menuoptions;
 mod menurequest;
+mod menuselection;
 mod menuspacing;
 mod menusize;
 mod menuuserptr;
 mod ncurseswmenuerror;
+mod popupmenu;
 
 pub use self::{
-    funcs::*, itemoption::*, itemoptions::*, menuoption::*, menuoptions::*,
-    menurequest::*, menuspacing::*, menusize::*, menuuserptr::*, ncurseswmenuerror::*
+    funcs::*, itemoption::*, itemoptions::*, menuaction::*, menubar::*,
+    menubaritem::*, menubarresult::*, menufilter::*, menufiltermode::*, menuhook::*,
+    menuinput::*, menuinputresult::*, menukeymap::*, menuoption::*, menuoptions::*,
+    menurequest::*, menuselection::*, menuspacing::*, menusize::*, menuuserptr::*,
+    ncurseswmenuerror::*, popupmenu::*
 };
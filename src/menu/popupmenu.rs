@@ -0,0 +1,163 @@
+/*
+    src/menu/popupmenu.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::convert::TryFrom;
+use crate::{
+    COLS, LINES, CharacterResult, KeyBinding, NCurseswError, Origin,
+    doupdate, getmaxyx, keypad, wget_wch,
+    panels::update_panels,
+    shims::bindings,
+    mouse::{
+        BUTTON1_PRESSED, BUTTON1_CLICKED, BUTTON1_DOUBLE_CLICKED, BUTTON1_TRIPLE_CLICKED,
+        BUTTON2_PRESSED, BUTTON2_CLICKED, BUTTON2_DOUBLE_CLICKED, BUTTON2_TRIPLE_CLICKED,
+        BUTTON3_PRESSED, BUTTON3_CLICKED, BUTTON3_DOUBLE_CLICKED, BUTTON3_TRIPLE_CLICKED,
+        mmask_t, getmouse, wenclose
+    },
+    menu::{
+        ITEM, MenuBarItem, MenuInputResult, MenuKeyMap, item_index,
+        menu_handle_input, menubar::MenuBarLevel, menufilter::item_at
+    }
+};
+
+// the mouse button presses that cancel the menu when outside of it.
+const BUTTON_PRESSES: i32 = BUTTON1_PRESSED | BUTTON1_CLICKED | BUTTON1_DOUBLE_CLICKED | BUTTON1_TRIPLE_CLICKED |
+                            BUTTON2_PRESSED | BUTTON2_CLICKED | BUTTON2_DOUBLE_CLICKED | BUTTON2_TRIPLE_CLICKED |
+                            BUTTON3_PRESSED | BUTTON3_CLICKED | BUTTON3_DOUBLE_CLICKED | BUTTON3_TRIPLE_CLICKED;
+
+/// Display a context (popup) menu of `labels` at `origin` (typically the screen
+/// position of a mouse event) and run it modally until an item is chosen or the
+/// menu is cancelled, returning the index of the chosen label.
+///
+/// The menu is sized using `scale_menu()` and displayed in a boxed window on top
+/// of the panel stack, if there is not enough room to the right of or below
+/// `origin` then the menu is flipped to the left or above `origin`.
+///
+/// Input is read from the menu's own window (with `keypad()` enabled) and processed
+/// by `menu_handle_input()` using `keymap` except that a mouse click on an item
+/// chooses it and a mouse button press outside of the menu cancels it, other mouse
+/// events (i.e. the release of the button that opened the menu) are ignored. All
+/// resources are released before returning.
+///
+/// ```text
+/// let event = getmouse(...)?;
+///
+/// match popup_menu(&["Cut", "Copy", "Paste"], Origin { y: event.y, x: event.x }, &MenuKeyMap::default())? {
+///     Some(0) => cut()?,
+///     Some(1) => copy()?,
+///     Some(2) => paste()?,
+///     _       => { }
+/// }
+/// ```
+pub fn popup_menu<S: AsRef<str>>(labels: &[S], origin: Origin, keymap: &MenuKeyMap) -> result!(Option<usize>) {
+    let items: Vec<MenuBarItem> = labels
+        .iter()
+        .enumerate()
+        .map(|(index, label)| MenuBarItem::command(label.as_ref(), index))
+        .collect();
+
+    let popup = MenuBarLevel::new_submenu(&items)?;
+    let size = getmaxyx(popup.window)?;
+
+    let origin = Origin {
+        y: if origin.y + size.lines > LINES() { origin.y + 1 - size.lines } else { origin.y },
+        x: if origin.x + size.columns > COLS() { origin.x + 1 - size.columns } else { origin.x }
+    };
+
+    keypad(popup.window, true)?;
+    popup.open(origin)?;
+
+    let chosen = loop {
+        update_panels();
+        doupdate()?;
+
+        let event = wget_wch(popup.window)?;
+
+        if event == CharacterResult::Key(KeyBinding::MouseEvent) {
+            if let Some(chosen) = popup_mouse_event(&popup)? {
+                break chosen;
+            }
+
+            continue;
+        }
+
+        match menu_handle_input(popup.menu, event, keymap)? {
+            MenuInputResult::Selected(item) => break Some(item),
+            MenuInputResult::Cancelled      => break None,
+            MenuInputResult::Unhandled      |
+            MenuInputResult::Continue       => { }
+        }
+    };
+
+    popup.close()?;
+
+    update_panels();
+    doupdate()?;
+
+    chosen.map(|item| Ok(usize::try_from(item_index(item)?)?)).transpose()
+}
+
+// the mouse event is read here rather than by `menu_driver()` so that the
+// button presses and clicks can be told apart, returns the chosen item (or
+// `None` if the menu was cancelled) once the menu is finished with.
+fn popup_mouse_event(popup: &MenuBarLevel) -> result!(Option<Option<ITEM>>) {
+    let mut event = bindings::MEVENT { id: 0, x: 0, y: 0, z: 0, bstate: 0 };
+
+    getmouse(&mut event)?;
+
+    let origin = Origin { y: event.y, x: event.x };
+
+    if !wenclose(popup.window, origin) {
+        Ok(if event.bstate & BUTTON_PRESSES as mmask_t != 0 { Some(None) } else { None })
+    } else if event.bstate & (BUTTON1_CLICKED | BUTTON1_DOUBLE_CLICKED) as mmask_t != 0 {
+        Ok(unsafe { item_at(popup.menu, origin) }?.map(Some))
+    } else {
+        Ok(None)
+    }
+}
+
+#[test]
+fn popup_menu_mouse_test() {
+    use crate::{funcs::with_test_screen, mouse::{ALL_MOUSE_EVENTS, BUTTON3_RELEASED, mousemask, ungetmouse}};
+
+    with_test_screen(|_| {
+        mousemask(ALL_MOUSE_EVENTS as mmask_t).unwrap();
+
+        let labels = ["Cut", "Copy", "Paste"];
+        let origin = Origin { y: 2, x: 2 };
+
+        let popup = |events: &[(i32, i32, i32)]| {
+            for (y, x, bstate) in events {
+                let mut event = bindings::MEVENT { id: 0, x: *x, y: *y, z: 0, bstate: *bstate as mmask_t };
+
+                ungetmouse(&mut event).unwrap();
+            }
+
+            popup_menu(&labels, origin, &MenuKeyMap::default()).unwrap()
+        };
+
+        // the release of the button that opened the menu is ignored, then
+        // "Copy" (in the box drawn around the menu) is clicked.
+        assert_eq!(popup(&[(3, 3, BUTTON3_RELEASED), (4, 3, BUTTON1_CLICKED)]), Some(1));
+        // a click outside of the menu cancels it.
+        assert_eq!(popup(&[(20, 40, BUTTON1_CLICKED)]), None);
+    });
+}
@@ -92,6 +92,15 @@ pub fn COLORS() -> i32 {
     ncurses::COLORS()
 }
 
+/// Screen function of `COLORS()`.
+///
+/// NCurses has no screen function of `COLORS` so the screen is made the
+/// current screen (see `set_term()`) while the number of colors is read, if
+/// there is no current screen then `screen` is left as the current screen.
+pub fn COLORS_sp(screen: SCREEN) -> i32 {
+    with_current_screen(screen, COLORS)
+}
+
 #[deprecated(since = "0.4.0", note = "use shims::ncurses::COLOR_PAIR() instead")]
 /// Return the attribute value of a given `normal` color pair.
 pub fn COLOR_PAIR(color_pair: i32) -> attr_t {
@@ -109,6 +118,27 @@ pub fn COLOR_PAIRS() -> i32 {
     ncurses::COLOR_PAIRS()
 }
 
+/// Screen function of `COLOR_PAIRS()`.
+///
+/// NCurses has no screen function of `COLOR_PAIRS` so the screen is made the
+/// current screen (see `set_term()`) while the number of color pairs is read,
+/// if there is no current screen then `screen` is left as the current screen.
+pub fn COLOR_PAIRS_sp(screen: SCREEN) -> i32 {
+    with_current_screen(screen, COLOR_PAIRS)
+}
+
+// call a function with a screen as the current screen, restoring the previous current screen.
+fn with_current_screen<T, F: FnOnce() -> T>(screen: SCREEN, func: F) -> T {
+    let previous = unsafe { ncurses::set_term(screen) };
+    let result = func();
+
+    if let Some(previous) = previous.filter(|previous| *previous != screen) {
+        unsafe { ncurses::set_term(previous) };
+    }
+
+    result
+}
+
 /// Return the number of columns (x-axis) available on the terminal.
 pub fn COLS() -> i32 {
     ncurses::COLS()
@@ -240,7 +270,7 @@ pub fn attr_get() -> result!(AttributesColorPairSet) {
     let mut opts: [i32; 1] = [0];
 
     match unsafe { ncurses::attr_get(attrs.as_mut_ptr(), color_pair.as_mut_ptr(), opts.as_mut_ptr() as *mut c_void) } {
-        OK => Ok(match ncurses_colortype() {
+        OK => Ok(match window_colortype(stdscr()) {
             NCursesColorType::Normal => {
                 AttributesColorPairSet::Normal(
                     normal::AttributesColorPair::new(
@@ -643,6 +673,8 @@ pub fn deleteln() -> result!(()) {
 /// data structure. The `endwin()` routine does not do this, so `delscreen()` should
 /// be called after `endwin()` if a particular `SCREEN` is no longer needed.
 pub fn delscreen(screen: SCREEN) {
+    remove_screen_colortype(screen);
+
     unsafe { ncurses::delscreen(screen) }
 }
 
@@ -953,7 +985,7 @@ pub fn getcchar(wcval: ComplexChar) -> result!(WideCharAndAttributes) {
     let opts: *mut i32 = ptr::null_mut();
 
     let attribute_colorpair_set = |attrs: attr_t, color_pair: short_t, ext_color_pair: i32| -> AttributesColorPairSet {
-        match ncurses_colortype() {
+        match window_colortype(ptr::null_mut()) {
             NCursesColorType::Normal   => {
                 AttributesColorPairSet::Normal(
                     normal::AttributesColorPair::new(
@@ -1353,12 +1385,10 @@ pub fn init_color(color_number: short_t, rgb: normal::RGB) -> result!(()) {
     if i32::from(color_number) >= COLORS() {
         Err(NCurseswError::ColorLimit)
     } else {
-        match ncurses::init_color(color_number, rgb.red(), rgb.green(), rgb.blue()) {
-            OK => {
-                set_ncurses_colortype(NCursesColorType::Normal);
+        set_screen_colortype(None, NCursesColorType::Normal)?;
 
-                Ok(())
-            },
+        match ncurses::init_color(color_number, rgb.red(), rgb.green(), rgb.blue()) {
+            OK => Ok(()),
             rc => Err(ncurses_function_error_with_rc!("init_color", rc))
         }
     }
@@ -1370,12 +1400,10 @@ pub fn init_extended_color(color_number: i32, rgb: extend::RGB) -> result!(()) {
     if color_number >= COLORS() {
         Err(NCurseswError::ColorLimit)
     } else {
-        match ncurses::init_extended_color(color_number, rgb.red(), rgb.green(), rgb.blue()) {
-            OK => {
-                set_ncurses_colortype(NCursesColorType::Extend);
+        set_screen_colortype(None, NCursesColorType::Extend)?;
 
-                Ok(())
-            },
+        match ncurses::init_extended_color(color_number, rgb.red(), rgb.green(), rgb.blue()) {
+            OK => Ok(()),
             rc => Err(ncurses_function_error_with_rc!("init_extended_color", rc))
         }
     }
@@ -1389,6 +1417,8 @@ pub fn init_extended_pair(color_pair: i32, colors: extend::Colors) -> result!(ex
     } else if colors.foreground().number() >= COLORS() || colors.background().number() >= COLORS() {
         Err(NCurseswError::ColorLimit)
     } else {
+        set_screen_colortype(None, NCursesColorType::Extend)?;
+
         match ncurses::init_extended_pair(color_pair, colors.foreground().number(), colors.background().number()) {
             OK => Ok(extend::ColorPair::_from(None, color_pair)),
             rc => Err(ncurses_function_error_with_rc!("init_extended_pair", rc))
@@ -1409,6 +1439,8 @@ pub fn init_pair(color_pair: short_t, colors: normal::Colors) -> result!(normal:
     } else if colors.foreground().number() >= COLORS() || colors.background().number() >= COLORS() {
         Err(NCurseswError::ColorLimit)
     } else {
+        set_screen_colortype(None, NCursesColorType::Normal)?;
+
         match ncurses::init_pair(color_pair, short_t::try_from(colors.foreground().number())?, short_t::try_from(colors.background().number())?) {
             OK => Ok(normal::ColorPair::_from(None, color_pair)),
             rc => Err(ncurses_function_error_with_rc!("init_pair", rc))
@@ -3993,7 +4025,7 @@ pub fn wattr_get(handle: WINDOW) -> result!(AttributesColorPairSet) {
     let mut opts: [i32; 1] = [0];
 
     match unsafe { ncurses::wattr_get(handle, attrs.as_mut_ptr(), color_pair.as_mut_ptr(), opts.as_mut_ptr() as *mut c_void) } {
-        OK => Ok(match ncurses_colortype() {
+        OK => Ok(match window_colortype(handle) {
                      NCursesColorType::Normal => {
                          AttributesColorPairSet::Normal(
                              normal::AttributesColorPair::new(
@@ -5109,12 +5141,10 @@ pub fn init_color_sp(screen: SCREEN, color_number: short_t, rgb: normal::RGB) ->
     if i32::from(color_number) >= COLORS() {
         Err(NCurseswError::ColorLimit)
     } else {
-        match unsafe { ncurses::init_color_sp(screen, color_number, rgb.red(), rgb.green(), rgb.blue()) } {
-            OK => {
-                set_ncurses_colortype(NCursesColorType::Normal);
+        set_screen_colortype(Some(screen), NCursesColorType::Normal)?;
 
-                Ok(())
-            },
+        match unsafe { ncurses::init_color_sp(screen, color_number, rgb.red(), rgb.green(), rgb.blue()) } {
+            OK => Ok(()),
             rc => Err(ncurses_function_error_with_rc!("init_color_sp", rc))
         }
     }
@@ -5126,12 +5156,10 @@ pub fn init_extended_color_sp(screen: SCREEN, color_number: i32, rgb: extend::RG
     if color_number >= COLORS() {
         Err(NCurseswError::ColorLimit)
     } else {
-        match unsafe { ncurses::init_extended_color_sp(screen, color_number, rgb.red(), rgb.green(), rgb.blue()) } {
-            OK => {
-                set_ncurses_colortype(NCursesColorType::Extend);
+        set_screen_colortype(Some(screen), NCursesColorType::Extend)?;
 
-                Ok(())
-            },
+        match unsafe { ncurses::init_extended_color_sp(screen, color_number, rgb.red(), rgb.green(), rgb.blue()) } {
+            OK => Ok(()),
             rc => Err(ncurses_function_error_with_rc!("init_extended_color_sp", rc))
         }
     }
@@ -5145,6 +5173,8 @@ pub fn init_extended_pair_sp(screen: SCREEN, color_pair: i32, colors: extend::Co
     } else if colors.foreground().number() >= COLORS() || colors.background().number() >= COLORS() {
         Err(NCurseswError::ColorLimit)
     } else {
+        set_screen_colortype(Some(screen), NCursesColorType::Extend)?;
+
         match unsafe { ncurses::init_extended_pair_sp(screen, color_pair, colors.foreground().number(), colors.background().number()) } {
             OK => Ok(extend::ColorPair::_from(Some(screen), color_pair)),
             rc => Err(ncurses_function_error_with_rc!("init_extended_pair_sp", rc))
@@ -5160,6 +5190,8 @@ pub fn init_pair_sp(screen: SCREEN, color_pair: short_t, colors: normal::Colors)
     } else if colors.foreground().number() >= COLORS() || colors.background().number() >= COLORS() {
         Err(NCurseswError::ColorLimit)
     } else {
+        set_screen_colortype(Some(screen), NCursesColorType::Normal)?;
+
         match unsafe { ncurses::init_pair_sp(screen, color_pair, short_t::try_from(colors.foreground().number())?, short_t::try_from(colors.background().number())?) } {
             OK => Ok(normal::ColorPair::_from(Some(screen), color_pair)),
             rc => Err(ncurses_function_error_with_rc!("init_pair_sp", rc))
@@ -5624,6 +5656,29 @@ pub fn termname_sp(screen: SCREEN) -> result!(String) {
     unsafe { ncurses::termname_sp(screen).ok_or(ncurses_function_error!("termname_sp")) }
 }
 
+/// Screen function of `tigetflag()`.
+pub fn tigetflag_sp(screen: SCREEN, capname: &str) -> result!(bool) {
+    match unsafe { ncurses::tigetflag_sp(screen, str_to_cstring_as_slice!(capname)) } {
+        -1 => Err(NCurseswError::InvalidCapability),
+        0  => Ok(false),
+        _  => Ok(true)
+    }
+}
+
+/// Screen function of `tigetnum()`.
+pub fn tigetnum_sp(screen: SCREEN, capname: &str) -> result!(Option<i32>) {
+    match unsafe { ncurses::tigetnum_sp(screen, str_to_cstring_as_slice!(capname)) } {
+        -2 => Err(NCurseswError::InvalidCapability),
+        -1 => Ok(None),
+        rc => Ok(Some(rc))
+    }
+}
+
+/// Screen function of `tigetstr()`.
+pub fn tigetstr_sp(screen: SCREEN, capname: &str) -> result!(Option<String>) {
+    Ok(unsafe { ncurses::tigetstr_sp(screen, str_to_cstring_as_slice!(capname)) })
+}
+
 /// Screen function of `typeahead()`.
 pub fn typeahead_sp<FD: AsRawFd + Read>(screen: SCREEN, file: Option<FD>) -> result!(()) {
     match unsafe { ncurses::typeahead_sp(screen, file.map_or_else(|| -1, |file| file.as_raw_fd())) } {
@@ -20,26 +20,31 @@
     IN THE SOFTWARE.
 */
 
-use std::{fmt::{Display, Formatter}, sync::atomic::{AtomicBool, Ordering}};
+use std::{
+    fmt::{Display, Formatter}, collections::HashMap, sync::{Mutex, PoisonError}
+};
+use crate::{NCurseswError, stdscr, shims::{bindings, ncurses::{SCREEN, WINDOW}}};
 
 lazy_static! {
-    // Flag internally to the crate if ansi or extended colors are being used,
-    // this is used by `attr_get()`, `wattr_get()` and `getcchar()` so that they
-    // can return the correct `attributes` and `colorpair` values.
+    // The color type of each screen keyed on the address of the screen (the
+    // functions not ending in `_sp` use the current screen, or the key 0 before
+    // a screen has been created), this is used by `attr_get()`,
+    // `wattr_get()` and `getcchar()` so that they can return the correct
+    // `attributes` and `colorpair` values for the screen of the window (or
+    // the current screen).
     //
-    // The flag is set by `init_extended_pair()`, `init_pair()`, `alloc_pair()`,
-    // `find_pair()`, `ColorPair::default()` and `Colors::new()`.
-    //
-    // This works on the assumption that the calling code will only ever use
-    // ansi or extended colors but not both (if both are inter-mixed then a
-    // panic will occur.
-    static ref EXTENDED_COLORS: AtomicBool = AtomicBool::new(false);
-    // Has the crates EXTENDED_COLORS been set.
-    static ref EXTENDED_COLORS_ALREADY_SET: AtomicBool = AtomicBool::new(false);
+    // The color type of a screen is only set by `set_ncurses_colortype()`,
+    // `init_extended_pair()`, `init_pair()`, `init_extended_color()`, `init_color()`,
+    // `alloc_pair()` and their screen functions, initialising colors or color
+    // pairs of the other color type on the same screen returns
+    // `NCurseswError::ColorTypeMismatch`. Creating a `Color`, `ColorPair` or
+    // `Colors` value of either color type does not set the color type.
+    static ref SCREEN_COLORTYPES: Mutex<HashMap<usize, NCursesColorType>> = Mutex::new(HashMap::new());
 }
 
 /// The color type that ncursesw is running under. by default it will be
-/// ColorType::Normal until a ColorPair structure is generated.
+/// ColorType::Normal until colors or color pairs are initialised (or the
+/// color type is set with `set_ncurses_colortype()`).
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum NCursesColorType {
     /// Normal (Ansi) colors, upto 16 colors.
@@ -58,35 +63,150 @@ impl Display for NCursesColorType {
 }
 
 /// Returns the color type that ncursesw is running under. by default it will be
-/// ColorType::Normal until a ColorPair structure is generated.
+/// ColorType::Normal until colors or color pairs are initialised (or the
+/// color type is set with `set_ncurses_colortype()`).
 pub fn ncurses_colortype() -> NCursesColorType {
-    if EXTENDED_COLORS.load(Ordering::SeqCst) {
-        NCursesColorType::Extend
-    } else {
-        NCursesColorType::Normal
-    }
+    screen_colortype(None).unwrap_or(NCursesColorType::Normal)
+}
+
+/// Screen function of `ncurses_colortype()`.
+pub fn ncurses_colortype_sp(screen: SCREEN) -> NCursesColorType {
+    screen_colortype(Some(screen)).unwrap_or(NCursesColorType::Normal)
 }
 
 /// Has the crates color type been set.
 pub fn ncurses_colortype_set() -> bool {
-    EXTENDED_COLORS_ALREADY_SET.load(Ordering::SeqCst)
+    screen_colortype(None).is_some()
 }
 
-pub(in crate) fn set_ncurses_colortype(colortype: NCursesColorType) {
-    let set_colortype = ncurses_colortype();
+/// Screen function of `ncurses_colortype_set()`.
+pub fn ncurses_colortype_set_sp(screen: SCREEN) -> bool {
+    screen_colortype(Some(screen)).is_some()
+}
 
-    if colortype != set_colortype {
-        if ncurses_colortype_set() {
-            panic!("ncursesw color type already set as {}!", set_colortype);
-        } else {
-            EXTENDED_COLORS_ALREADY_SET.store(true, Ordering::SeqCst);
+/// Set the color type that ncursesw is running under on the current screen
+/// (or all screens if no screen has been created) before any colors or
+/// color pairs are initialised, returns `NCurseswError::ColorTypeMismatch`
+/// if the color type has already been set to the other color type.
+pub fn set_ncurses_colortype(colortype: NCursesColorType) -> result!(()) {
+    set_screen_colortype(None, colortype)
+}
 
-            EXTENDED_COLORS.store(match colortype {
-                    NCursesColorType::Normal => false,
-                    NCursesColorType::Extend => true
-                },
-                Ordering::SeqCst
-            );
-        }
+/// Screen function of `set_ncurses_colortype()`.
+pub fn set_ncurses_colortype_sp(screen: SCREEN, colortype: NCursesColorType) -> result!(()) {
+    set_screen_colortype(Some(screen), colortype)
+}
+
+// the key of a screen, the functions not ending in `_sp` use the current
+// screen (the screen of `stdscr()`, see `set_term()`) or 0 before a screen
+// has been created with `initscr()` or `newterm()`.
+fn screen_key(screen: Option<SCREEN>) -> usize {
+    screen.unwrap_or_else(|| window_screen(stdscr())) as usize
+}
+
+// the screen of a window, null if the window is null.
+fn window_screen(window: WINDOW) -> SCREEN {
+    // `_nc_screen_of()` is internal to NCurses but is exported by the wide
+    // library (the `_sp` functions are built on it) both by the NCurses built
+    // by build.rs and the distribution libraries, it returns null for a null window.
+    unsafe { bindings::_nc_screen_of(window) }
+}
+
+// the color type of a screen, if the color type of the screen has not been
+// set then the color type set before a screen was created is used.
+// a poisoned lock is recovered as the map of color types is always consistent.
+fn screen_colortype(screen: Option<SCREEN>) -> Option<NCursesColorType> {
+    let colortypes = SCREEN_COLORTYPES.lock().unwrap_or_else(PoisonError::into_inner);
+
+    colortypes.get(&screen_key(screen)).or_else(|| colortypes.get(&0)).copied()
+}
+
+// set the color type of a screen when colors or color pairs are initialised.
+pub(in crate) fn set_screen_colortype(screen: Option<SCREEN>, colortype: NCursesColorType) -> result!(()) {
+    let mut colortypes = SCREEN_COLORTYPES.lock().unwrap_or_else(PoisonError::into_inner);
+    let initial = colortypes.get(&0).copied().unwrap_or(colortype);
+
+    match *colortypes.entry(screen_key(screen)).or_insert(initial) {
+        set_colortype if set_colortype == colortype => Ok(()),
+        set_colortype                               => Err(NCurseswError::ColorTypeMismatch { colortype: set_colortype, requested: colortype })
     }
 }
+
+// the color type of the screen of a window (the current screen when `window` is null).
+pub(in crate) fn window_colortype(window: WINDOW) -> NCursesColorType {
+    let screen = window_screen(if window.is_null() { stdscr() } else { window });
+
+    screen_colortype(if screen.is_null() { None } else { Some(screen) }).unwrap_or(NCursesColorType::Normal)
+}
+
+// forget the color type of a deleted screen (the address may be reused).
+pub(in crate) fn remove_screen_colortype(screen: SCREEN) {
+    SCREEN_COLORTYPES.lock().unwrap_or_else(PoisonError::into_inner).remove(&screen_key(Some(screen)));
+}
+
+#[test]
+fn screen_colortype_test() {
+    let (first, second) = (0x10 as SCREEN, 0x20 as SCREEN);
+
+    assert!(set_screen_colortype(Some(first), NCursesColorType::Normal).is_ok());
+    assert!(set_screen_colortype(Some(second), NCursesColorType::Extend).is_ok());
+    assert_eq!(
+        set_screen_colortype(Some(first), NCursesColorType::Extend),
+        Err(NCurseswError::ColorTypeMismatch { colortype: NCursesColorType::Normal, requested: NCursesColorType::Extend })
+    );
+
+    assert_eq!(ncurses_colortype_sp(first), NCursesColorType::Normal);
+    assert_eq!(ncurses_colortype_sp(second), NCursesColorType::Extend);
+
+    remove_screen_colortype(first);
+
+    assert!(!ncurses_colortype_set_sp(first));
+    assert!(set_screen_colortype(Some(first), NCursesColorType::Extend).is_ok());
+}
+
+#[test]
+fn window_colortype_test() {
+    crate::funcs::with_test_screen(|screen| {
+        set_screen_colortype(Some(screen), NCursesColorType::Extend).unwrap();
+
+        assert_eq!(window_colortype(stdscr()), NCursesColorType::Extend);
+        assert_eq!(window_colortype(std::ptr::null_mut()), NCursesColorType::Extend);
+    });
+}
+
+#[test]
+fn current_screen_colortype_test() {
+    use std::fs::OpenOptions;
+    use crate::{newterm, set_term, delscreen};
+
+    crate::funcs::with_test_screen(|first| {
+        let terminal = OpenOptions::new().read(true).write(true).open("/dev/null").unwrap();
+        let second = newterm(None::<String>, &terminal, &terminal).unwrap();
+
+        // `newterm()` makes the second screen the current screen.
+        set_ncurses_colortype(NCursesColorType::Extend).unwrap();
+
+        assert_eq!(ncurses_colortype_sp(second), NCursesColorType::Extend);
+        assert!(!ncurses_colortype_set_sp(first));
+
+        set_term(first).unwrap();
+        set_ncurses_colortype(NCursesColorType::Normal).unwrap();
+
+        assert_eq!(ncurses_colortype(), NCursesColorType::Normal);
+        assert_eq!(window_colortype(std::ptr::null_mut()), NCursesColorType::Normal);
+        assert_eq!(
+            set_ncurses_colortype_sp(first, NCursesColorType::Extend),
+            Err(NCurseswError::ColorTypeMismatch { colortype: NCursesColorType::Normal, requested: NCursesColorType::Extend })
+        );
+
+        set_term(second).unwrap();
+
+        assert_eq!(ncurses_colortype(), NCursesColorType::Extend);
+
+        let _ = crate::endwin();
+        delscreen(second);
+
+        // `set_term()` returns the previous screen which has been deleted.
+        let _ = set_term(first);
+    });
+}
@@ -24,7 +24,7 @@ use std::{num, char, ffi, convert};
 use errno::{errno, Errno};
 use thiserror::Error;
 use crate::{
-    COLORS, COLOR_PAIRS, panels::NCurseswPanelsError, mouse::NCurseswMouseError,
+    COLORS, COLOR_PAIRS, NCursesColorType, panels::NCurseswPanelsError, mouse::NCurseswMouseError,
     menu::NCurseswMenuError, form::NCurseswFormError, shims::ncurses::wint_t
 };
 
@@ -46,6 +46,12 @@ pub enum NCurseswError {
     ColorLimit,
     #[error("Terminal only supports a maximum of {} color pairs", COLOR_PAIRS())]
     ColorPairLimit,
+    #[error("Color type already set as {colortype}, unable to use {requested} colors")]
+    ColorTypeMismatch { colortype: NCursesColorType, requested: NCursesColorType },
+    #[error("Terminal does not support direct colors")]
+    DirectColorUnsupported,
+    #[error("theme line {line} : {message}")]
+    ThemeParseError { line: usize, message: String },
     #[error("Invalid capability")]
     InvalidCapability,
     #[error("WideChar to char error with a value of {inner}!")]
@@ -25,7 +25,6 @@
 use crate::{
     NCurseswError,
     gen::ColorType,
-    ncursescolortype::{set_ncurses_colortype, NCursesColorType},
     shims::ncurses::{short_t, SCREEN},
     normal::{ColorPalette, RGB},
     ncurses::{init_color, color_content, init_color_sp, color_content_sp}
@@ -42,8 +41,6 @@ impl Color {
     pub(in crate) fn _from(screen: Option<SCREEN>, color_palette: ColorPalette) -> Self {
         assert!(screen.map_or_else(|| true, |screen| !screen.is_null()), "Color::_from() : screen.is_null()");
 
-        set_ncurses_colortype(NCursesColorType::Normal);
-
         Self { screen, color_palette }
     }
 }
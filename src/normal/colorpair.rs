@@ -29,7 +29,6 @@ use crate::{
         ColorsType, ColorPairType, ColorPairGeneric,
         ColorPairColors, AttributesType
     },
-    ncursescolortype::{set_ncurses_colortype, NCursesColorType},
     shims::ncurses::{attr_t, short_t},
     normal::{Attribute, Attributes, Colors, Color},
     ncurses::{
@@ -49,8 +48,6 @@ impl ColorPair {
     pub(in crate) fn _from(screen: Option<SCREEN>, number: short_t) -> Self {
         assert!(screen.map_or_else(|| true, |screen| !screen.is_null()), "ColorPair::_from() : screen.is_null()");
 
-        set_ncurses_colortype(NCursesColorType::Normal);
-
         Self { screen, number }
     }
 }
@@ -24,6 +24,7 @@ use std::{ptr, convert::TryFrom};
 use crate::{
     SCREEN, NCurseswError,
     gen::{ColorType, ColorsType},
+    ncursescolortype::{set_screen_colortype, NCursesColorType},
     shims::{ncurses, ncurses::short_t},
     normal::{ColorPair, Colors}
 };
@@ -43,6 +44,8 @@ use crate::{
 pub fn alloc_pair(colors: Colors) -> result!(ColorPair) {
     assert!(colors.screen().is_none(), "alloc_pair() : colors.screen().is_some()");
 
+    set_screen_colortype(None, NCursesColorType::Normal)?;
+
     let number = ncurses::alloc_pair(colors.foreground().number(), colors.background().number());
 
     if number.is_negative() {
@@ -70,6 +73,8 @@ pub fn find_pair(colors: Colors) -> result!(Option<ColorPair>) {
 pub fn alloc_pair_sp(screen: SCREEN, colors: Colors) -> result!(ColorPair) {
     assert!(colors.screen().map_or_else(|| false, |colors_scr| ptr::eq(screen, colors_scr)), "alloc_pair_sp() : screen.is_null() || screen != colors.screen()");
 
+    set_screen_colortype(Some(screen), NCursesColorType::Normal)?;
+
     let number = unsafe { ncurses::alloc_pair_sp(screen, colors.foreground().number(), colors.background().number()) };
 
     if number.is_negative() {
@@ -26,11 +26,12 @@ mod attributescolorpair;
 mod color;
 mod colorpalette;
 mod colorpair;
+mod colorpairpool;
 mod colors;
 mod funcs;
 mod rgb;
 
 pub use self::{
     attribute::*, attributes::*, attributescolorpair::*, color::*,
-    colorpalette::*, colorpair::*, colors::*, funcs::*, rgb::*
+    colorpalette::*, colorpair::*, colorpairpool::*, colors::*, funcs::*, rgb::*
 };
@@ -25,3 +25,31 @@ use crate::shims::ncurses::short_t;
 include!("../include/rgb.rs");
 
 define_rgb!(short_t);
+
+#[test]
+fn rgb_test() {
+    let orange = RGB::from_hex("#ff8000").unwrap();
+
+    assert_eq!(orange, RGB::new(1000, 502, 0));
+    assert_eq!(orange.to_rgb8(), (255, 128, 0));
+    assert_eq!(orange.to_hex(), "#ff8000");
+    assert_eq!("#f80".parse::<RGB>().unwrap().to_hex(), "#ff8800");
+    assert!(RGB::from_hex("ff8000").is_err());
+    assert!(RGB::from_hex("#ff80").is_err());
+    assert!(RGB::from_hex("#ff80zz").is_err());
+
+    let (hue, saturation, lightness) = orange.to_hsl();
+
+    assert!((hue - 30.1).abs() < 0.1 && (saturation - 1.0).abs() < 1e-9 && (lightness - 0.5).abs() < 1e-9);
+    assert_eq!(RGB::from_hsl(hue, saturation, lightness), orange);
+    assert_eq!(RGB::from_hsv(120.0, 1.0, 1.0), RGB::new(0, 1000, 0));
+    assert_eq!(RGB::new(0, 1000, 0).to_hsv(), (120.0, 1.0, 1.0));
+
+    let (black, white) = (RGB::new(0, 0, 0), RGB::new(1000, 1000, 1000));
+
+    assert_eq!(black.blend(white, 0.5), RGB::new(500, 500, 500));
+    assert_eq!(black.lighten(0.25), RGB::new(250, 250, 250));
+    assert_eq!(white.darken(1.0), black);
+    assert!((black.contrast_ratio(white) - 21.0).abs() < 1e-9);
+    assert!((white.contrast_ratio(white) - 1.0).abs() < 1e-9);
+}
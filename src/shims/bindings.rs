@@ -28,7 +28,7 @@
 #![allow(clippy::unreadable_literal)]
 #![allow(clippy::redundant_static_lifetimes)]
 
-use std::os::raw::{c_short, c_int};
+use std::os::raw::{c_char, c_short, c_int};
 
 pub type ITEM = tagITEM;
 pub type MENU = tagMENU;
@@ -40,6 +40,15 @@ extern "C" {
     pub fn getcchar(_: *const cchar_t, _: *mut wchar_t, _: *mut attr_t, _: *mut c_short, _: *mut c_int) -> c_int;
     pub fn ripoffline(_: c_int, _: RipoffInit) -> c_int;
     pub fn ripoffline_sp(_: *mut SCREEN, _: c_int, _: RipoffInit) -> c_int;
+    pub fn _nc_screen_of(_: *mut WINDOW) -> *mut SCREEN;
+    pub fn tigetflag_sp(_: *mut SCREEN, _: *const c_char) -> c_int;
+    pub fn tigetnum_sp(_: *mut SCREEN, _: *const c_char) -> c_int;
+    pub fn tigetstr_sp(_: *mut SCREEN, _: *const c_char) -> *mut c_char;
+}
+
+// C library functions.
+extern "C" {
+    pub fn wcwidth(_: wchar_t) -> c_int;
 }
 
 // bingen output.
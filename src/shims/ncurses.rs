@@ -2684,7 +2684,16 @@ pub fn tigetnum(capname: &[i8]) -> i32 {
 
 /// <https://invisible-island.net/ncurses/man/curs_terminfo.3x.html>
 pub fn tigetstr(capname: &[i8]) -> Option<String> {
-    unsafe { (bindings::tigetstr(capname.as_ptr()) as *mut i8).as_mut().map(|ptr| FromCStr::from_c_str(ptr)) }
+    unsafe { cancelled_string_as_mut(bindings::tigetstr(capname.as_ptr())).map(|ptr| FromCStr::from_c_str(ptr)) }
+}
+
+// `tigetstr()` returns `(char *)-1` if the capability is not a string capability.
+unsafe fn cancelled_string_as_mut<'a>(ptr: *mut libc::c_char) -> Option<&'a mut i8> {
+    if ptr as isize == -1 {
+        None
+    } else {
+        (ptr as *mut i8).as_mut()
+    }
 }
 
 /// <https://invisible-island.net/ncurses/man/curs_inopts.3x.html>
@@ -4567,6 +4576,33 @@ pub unsafe fn termname_sp(sp: SCREEN) -> Option<String> {
     (bindings::termname() as *mut i8).as_mut().map(|ptr| FromCStr::from_c_str(ptr))
 }
 
+/// # Safety
+///
+/// <https://invisible-island.net/ncurses/man/curs_sp_funcs.3x.html>
+pub unsafe fn tigetflag_sp(sp: SCREEN, capname: &[i8]) -> i32 {
+    assert!(!sp.is_null(), "{}tigetflag_sp() : sp.is_null()", MODULE_PATH);
+
+    bindings::tigetflag_sp(sp, capname.as_ptr())
+}
+
+/// # Safety
+///
+/// <https://invisible-island.net/ncurses/man/curs_sp_funcs.3x.html>
+pub unsafe fn tigetnum_sp(sp: SCREEN, capname: &[i8]) -> i32 {
+    assert!(!sp.is_null(), "{}tigetnum_sp() : sp.is_null()", MODULE_PATH);
+
+    bindings::tigetnum_sp(sp, capname.as_ptr())
+}
+
+/// # Safety
+///
+/// <https://invisible-island.net/ncurses/man/curs_sp_funcs.3x.html>
+pub unsafe fn tigetstr_sp(sp: SCREEN, capname: &[i8]) -> Option<String> {
+    assert!(!sp.is_null(), "{}tigetstr_sp() : sp.is_null()", MODULE_PATH);
+
+    cancelled_string_as_mut(bindings::tigetstr_sp(sp, capname.as_ptr())).map(|ptr| FromCStr::from_c_str(ptr))
+}
+
 /// # Safety
 ///
 /// <https://invisible-island.net/ncurses/man/curs_sp_funcs.3x.html>
@@ -0,0 +1,214 @@
+/*
+    src/theme/style.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::convert::TryFrom;
+use crate::{
+    SCREEN, NCurseswError, COLORS, COLORS_sp, AttributesColorPairSet, NCursesColorType, attr_t,
+    shims::{ncurses::short_t, constants::A_NORMAL},
+    normal, extend::{self, ColorDepth},
+    theme::StyleColor
+};
+
+/// A named style of a `Theme`, a foreground and background color with attributes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Style {
+    foreground: StyleColor,
+    background: StyleColor,
+    attributes: attr_t
+}
+
+impl Style {
+    /// Create a new style with no attributes.
+    pub fn new(foreground: StyleColor, background: StyleColor) -> Self {
+        Self { foreground, background, attributes: A_NORMAL }
+    }
+
+    /// Add an attribute to the style.
+    pub fn set_attribute(mut self, attribute: extend::Attribute) -> Self {
+        let attribute: attr_t = attribute.into();
+
+        self.attributes |= attribute;
+
+        self
+    }
+
+    /// The foreground color of the style.
+    pub fn foreground(&self) -> StyleColor {
+        self.foreground
+    }
+
+    /// The background color of the style.
+    pub fn background(&self) -> StyleColor {
+        self.background
+    }
+
+    /// The raw attributes of the style.
+    pub fn attributes(&self) -> attr_t {
+        self.attributes
+    }
+
+    /// Resolve the style to attributes and a color pair of a color type,
+    /// the color pair is allocated with `alloc_pair()`.
+    ///
+    /// Red, green and blue colors are degraded to the nearest color the
+    /// terminal supports (see `extend::ColorDepth`), `normal` colors are
+    /// limited to the 8 ansi colors on a direct color terminal. On a monochrome
+    /// terminal color pair 0 is used.
+    pub fn resolve(&self, colortype: NCursesColorType) -> result!(AttributesColorPairSet) {
+        self._resolve(None, colortype)
+    }
+
+    /// Screen function of `resolve()`.
+    pub fn resolve_sp(&self, screen: SCREEN, colortype: NCursesColorType) -> result!(AttributesColorPairSet) {
+        self._resolve(Some(screen), colortype)
+    }
+
+    fn _resolve(&self, screen: Option<SCREEN>, colortype: NCursesColorType) -> result!(AttributesColorPairSet) {
+        self._resolve_with(
+            screen,
+            colortype,
+            |colors| match screen {
+                None         => normal::alloc_pair(colors),
+                Some(screen) => normal::alloc_pair_sp(screen, colors)
+            },
+            |colors| match screen {
+                None         => extend::alloc_pair(colors),
+                Some(screen) => extend::alloc_pair_sp(screen, colors)
+            }
+        )
+    }
+
+    // resolve the style allocating the color pair of the colors with
+    // `alloc_normal` or `alloc_extend` (see `ThemeManager`).
+    pub(in crate) fn _resolve_with<N, E>(
+        &self,
+        screen: Option<SCREEN>,
+        colortype: NCursesColorType,
+        alloc_normal: N,
+        alloc_extend: E
+    ) -> result!(AttributesColorPairSet)
+        where N: FnOnce(normal::Colors) -> result!(normal::ColorPair),
+              E: FnOnce(extend::Colors) -> result!(extend::ColorPair)
+    {
+        let depth = ColorDepth::_detect(screen);
+
+        match colortype {
+            NCursesColorType::Normal => {
+                // direct colors do not fit a normal color, above 8 they are not palette colors.
+                let depth = if depth == ColorDepth::Direct { ColorDepth::Ansi8 } else { depth };
+                let attributes = normal::Attributes::_from(screen, self.attributes);
+
+                let color_pair = match (color_number(screen, depth, self.foreground)?, color_number(screen, depth, self.background)?) {
+                    (Some(foreground), Some(background)) => {
+                        let color = |number: i32| -> result!(normal::Color) {
+                            Ok(normal::Color::_from(screen, normal::ColorPalette::_from(short_t::try_from(number)?)))
+                        };
+                        let colors = normal::Colors::new(color(foreground)?, color(background)?);
+
+                        alloc_normal(colors)?
+                    },
+                    _                                    => normal::ColorPair::_from(screen, 0)
+                };
+
+                Ok(AttributesColorPairSet::Normal(normal::AttributesColorPair::new(attributes, color_pair)))
+            },
+            NCursesColorType::Extend => {
+                let attributes = extend::Attributes::_from(screen, self.attributes);
+
+                let color_pair = match (color_number(screen, depth, self.foreground)?, color_number(screen, depth, self.background)?) {
+                    (Some(foreground), Some(background)) => {
+                        let color = |number: i32| extend::Color::_from(screen, extend::ColorPalette::_from(number));
+                        let colors = extend::Colors::new(color(foreground), color(background));
+
+                        alloc_extend(colors)?
+                    },
+                    _                                    => extend::ColorPair::_from(screen, 0)
+                };
+
+                Ok(AttributesColorPairSet::Extend(extend::AttributesColorPair::new(attributes, color_pair)))
+            }
+        }
+    }
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self::new(StyleColor::default(), StyleColor::default())
+    }
+}
+
+// the number of a style color, or `None` on a monochrome terminal.
+fn color_number(screen: Option<SCREEN>, depth: ColorDepth, color: StyleColor) -> result!(Option<i32>) {
+    match (depth, color) {
+        (ColorDepth::Monochrome, _)            => Ok(None),
+        (_, StyleColor::Palette(color_palette)) => palette_number(screen, depth, color_palette.number()).map(Some),
+        (_, StyleColor::Rgb(rgb))               => depth.nearest_number(screen, rgb)
+    }
+}
+
+// the light colors of the palette are the dark colors on a terminal with 8
+// colors (on a direct color terminal only the first 8 colors are palette colors).
+fn palette_number(screen: Option<SCREEN>, depth: ColorDepth, number: i32) -> result!(i32) {
+    let number = if (depth == ColorDepth::Ansi8 || depth == ColorDepth::Direct) && (8..16).contains(&number) {
+        number - 8
+    } else {
+        number
+    };
+
+    if number < screen.map_or_else(COLORS, COLORS_sp) {
+        Ok(number)
+    } else {
+        Err(NCurseswError::ColorLimit)
+    }
+}
+
+#[test]
+fn style_resolve_sp_test() {
+    use std::fs::OpenOptions;
+    use crate::{
+        ColorPairColors, ColorType, ColorsType, newterm, set_term, delscreen, endwin, start_color_sp,
+        use_default_colors_sp, extend::RGB
+    };
+
+    crate::funcs::with_test_screen(|first| {
+        start_color_sp(first).unwrap();
+        use_default_colors_sp(first).unwrap();
+
+        let terminal = OpenOptions::new().read(true).write(true).open("/dev/null").unwrap();
+
+        std::env::set_var("TERM", "xterm-256color");
+
+        let second = newterm(None::<String>, &terminal, &terminal).unwrap();
+
+        start_color_sp(second).unwrap();
+
+        // red is degraded to the 8 colors of the first screen rather than the 256 colors of the current screen.
+        let style = Style::new(StyleColor::Rgb(RGB::from_rgb8(255, 0, 0)), StyleColor::default());
+        let color_pair = style.resolve_sp(first, NCursesColorType::Normal).unwrap().unwrap_as_normal().color_pair();
+
+        assert_eq!(color_pair.colors().unwrap().foreground().number(), 1);
+
+        let _ = endwin();
+        delscreen(second);
+        let _ = set_term(first);
+    });
+}
@@ -0,0 +1,54 @@
+/*
+    src/theme/stylecolor.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::str::FromStr;
+use crate::{NCurseswError, extend::{ColorPalette, RGB}};
+
+/// A foreground or background color of a `Style`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum StyleColor {
+    /// A color of the palette.
+    Palette(ColorPalette),
+    /// A red, green and blue color, degraded to the nearest color the
+    /// terminal supports (see `extend::ColorDepth`).
+    Rgb(RGB)
+}
+
+impl Default for StyleColor {
+    fn default() -> Self {
+        StyleColor::Palette(ColorPalette::TerminalDefault)
+    }
+}
+
+impl FromStr for StyleColor {
+    type Err = NCurseswError;
+
+    /// Parse a color of the palette (see `ColorPalette::from_str()`) or a
+    /// `#rrggbb` or `#rgb` hex string (see `RGB::from_hex()`).
+    fn from_str(color: &str) -> Result<Self, Self::Err> {
+        if color.starts_with('#') {
+            Ok(StyleColor::Rgb(RGB::from_hex(color)?))
+        } else {
+            Ok(StyleColor::Palette(ColorPalette::from_str(color)?))
+        }
+    }
+}
@@ -0,0 +1,300 @@
+/*
+    src/theme/theme.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{fs, path::Path, str::FromStr, iter::Peekable, collections::HashMap};
+use errno::Errno;
+use crate::{
+    NCurseswError,
+    extend::Attribute,
+    theme::{Style, StyleColor}
+};
+
+/// A theme of named styles.
+///
+/// A theme is parsed from text with a style on each line, the colors are a
+/// color of the palette (see `ColorPalette::from_str()`) or a `#rrggbb` or
+/// `#rgb` hex string, the default for both colors is `"default"`. The attributes
+/// are any of `"normal"`, `"standout"`, `"underline"`, `"reverse"`, `"blink"`,
+/// `"dim"`, `"bold"`, `"altcharset"`, `"invisible"`, `"protected"` or `"italic"`.
+/// Comments start with `#`.
+///
+/// ```text
+/// # the dark theme.
+/// "error"  = { fg = "red", bg = "default", attrs = ["bold"] }
+/// "title"  = { fg = "#ffaf00", attrs = ["bold", "underline"] }
+/// "window" = { fg = "light white", bg = "#262626" }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Theme {
+    styles: HashMap<String, Style>
+}
+
+impl Theme {
+    /// Create a new theme with no styles.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a theme from a file, a file that is not valid UTF-8 is reported
+    /// as `NCurseswError::ThemeParseError` with the line of the first invalid byte.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> result!(Self) {
+        let bytes = fs::read(path).map_err(|source| {
+            NCurseswError::OSError { func: "Theme::from_file".to_string(), errno: Errno(source.raw_os_error().unwrap_or(0)) }
+        })?;
+
+        let text = String::from_utf8(bytes).map_err(|source| {
+            let valid = &source.as_bytes()[..source.utf8_error().valid_up_to()];
+
+            NCurseswError::ThemeParseError {
+                line:    valid.iter().filter(|byte| **byte == b'\n').count() + 1,
+                message: source.utf8_error().to_string()
+            }
+        })?;
+
+        Self::from_str(&text)
+    }
+
+    /// The named style.
+    pub fn style(&self, name: &str) -> Option<&Style> {
+        self.styles.get(name)
+    }
+
+    /// Set a named style, returning the style it replaces.
+    pub fn set_style<S: Into<String>>(&mut self, name: S, style: Style) -> Option<Style> {
+        self.styles.insert(name.into(), style)
+    }
+
+    /// The named styles of the theme.
+    pub fn styles(&self) -> &HashMap<String, Style> {
+        &self.styles
+    }
+}
+
+impl FromStr for Theme {
+    type Err = NCurseswError;
+
+    /// Parse a theme, an error is returned with the line number and the reason
+    /// of the first line that can not be parsed.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut theme = Self::new();
+
+        for (number, line) in text.lines().enumerate() {
+            let error = |message: String| NCurseswError::ThemeParseError { line: number + 1, message };
+            let tokens = tokenize(line).map_err(error)?;
+
+            if !tokens.is_empty() {
+                let (name, style) = parse_style(tokens).map_err(error)?;
+
+                if theme.styles.contains_key(&name) {
+                    return Err(error(format!("duplicate style \"{}\"", name)));
+                }
+
+                theme.styles.insert(name, style);
+            }
+        }
+
+        Ok(theme)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Text(String),
+    Equals,
+    OpenBrace,
+    CloseBrace,
+    OpenBracket,
+    CloseBracket,
+    Comma
+}
+
+// split a line into tokens, a quoted string or a bare word is a `Token::Text`.
+fn tokenize(line: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = line.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '#'                                    => break,
+            '='                                    => tokens.push(Token::Equals),
+            '{'                                    => tokens.push(Token::OpenBrace),
+            '}'                                    => tokens.push(Token::CloseBrace),
+            '['                                    => tokens.push(Token::OpenBracket),
+            ']'                                    => tokens.push(Token::CloseBracket),
+            ','                                    => tokens.push(Token::Comma),
+            '"'                                    => {
+                let mut text = String::new();
+
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(ch)  => text.push(ch),
+                        None      => return Err("unterminated string".to_string())
+                    }
+                }
+
+                tokens.push(Token::Text(text));
+            },
+            ch if ch.is_whitespace()               => { },
+            ch if ch.is_alphanumeric() || ch == '_' => {
+                let mut text = ch.to_string();
+
+                while let Some(ch) = chars.next_if(|ch| ch.is_alphanumeric() || *ch == '_' || *ch == '-') {
+                    text.push(ch);
+                }
+
+                tokens.push(Token::Text(text));
+            },
+            ch                                     => return Err(format!("unexpected '{}'", ch))
+        }
+    }
+
+    Ok(tokens)
+}
+
+// parse `name = { key = value, ... }`.
+fn parse_style(tokens: Vec<Token>) -> Result<(String, Style), String> {
+    let mut tokens = tokens.into_iter().peekable();
+
+    let name = text(&mut tokens, "a style name")?;
+
+    expect(&mut tokens, Token::Equals)?;
+    expect(&mut tokens, Token::OpenBrace)?;
+
+    let (mut foreground, mut background) = (StyleColor::default(), StyleColor::default());
+    let mut attributes = vec![];
+
+    while tokens.next_if_eq(&Token::CloseBrace).is_none() {
+        let key = text(&mut tokens, "a key")?;
+
+        expect(&mut tokens, Token::Equals)?;
+
+        match key.as_str() {
+            "fg"    => foreground = color(&mut tokens)?,
+            "bg"    => background = color(&mut tokens)?,
+            "attrs" => {
+                expect(&mut tokens, Token::OpenBracket)?;
+
+                while tokens.next_if_eq(&Token::CloseBracket).is_none() {
+                    let name = text(&mut tokens, "an attribute")?;
+
+                    attributes.push(attribute(&name).ok_or_else(|| format!("unknown attribute \"{}\"", name))?);
+
+                    if tokens.next_if_eq(&Token::Comma).is_none() && tokens.peek() != Some(&Token::CloseBracket) {
+                        return Err("expected ',' or ']'".to_string());
+                    }
+                }
+            },
+            key     => return Err(format!("unknown key \"{}\"", key))
+        }
+
+        if tokens.next_if_eq(&Token::Comma).is_none() && tokens.peek() != Some(&Token::CloseBrace) {
+            return Err("expected ',' or '}'".to_string());
+        }
+    }
+
+    if tokens.next().is_some() {
+        return Err("unexpected text after '}'".to_string());
+    }
+
+    let style = attributes.into_iter().fold(Style::new(foreground, background), Style::set_attribute);
+
+    Ok((name, style))
+}
+
+fn text<I: Iterator<Item = Token>>(tokens: &mut Peekable<I>, expected: &str) -> Result<String, String> {
+    match tokens.next() {
+        Some(Token::Text(text)) => Ok(text),
+        _                       => Err(format!("expected {}", expected))
+    }
+}
+
+fn expect<I: Iterator<Item = Token>>(tokens: &mut Peekable<I>, expected: Token) -> Result<(), String> {
+    if tokens.next_if_eq(&expected).is_some() {
+        Ok(())
+    } else {
+        Err(format!("expected {:?}", expected))
+    }
+}
+
+fn color<I: Iterator<Item = Token>>(tokens: &mut Peekable<I>) -> Result<StyleColor, String> {
+    StyleColor::from_str(&text(tokens, "a color")?).map_err(|source| source.to_string())
+}
+
+fn attribute(name: &str) -> Option<Attribute> {
+    match name {
+        "normal"     => Some(Attribute::Normal),
+        "standout"   => Some(Attribute::Standout),
+        "underline"  => Some(Attribute::Underline),
+        "reverse"    => Some(Attribute::Reverse),
+        "blink"      => Some(Attribute::Blink),
+        "dim"        => Some(Attribute::Dim),
+        "bold"       => Some(Attribute::Bold),
+        "altcharset" => Some(Attribute::AlternativeCharSet),
+        "invisible"  => Some(Attribute::Invisible),
+        "protected"  => Some(Attribute::Protected),
+        "italic"     => Some(Attribute::Italic),
+        _            => None
+    }
+}
+
+#[test]
+fn theme_test() {
+    use crate::extend::{ColorPalette, RGB};
+
+    let theme = Theme::from_str(r##"
+        # a comment
+        "error" = { fg = "red", bg = "default", attrs = ["bold", "underline"] }
+        title = { fg = "#ffaf00" }   # trailing comment
+        "status bar" = { bg = "light blue", attrs = [] }
+    "##).unwrap();
+
+    assert_eq!(theme.styles().len(), 3);
+    assert_eq!(
+        theme.style("error"),
+        Some(&Style::new(StyleColor::Palette(ColorPalette::Red), StyleColor::default()).set_attribute(Attribute::Bold).set_attribute(Attribute::Underline))
+    );
+    assert_eq!(theme.style("title"), Some(&Style::new(StyleColor::Rgb(RGB::from_rgb8(255, 175, 0)), StyleColor::default())));
+    assert_eq!(theme.style("status bar").map(Style::background), Some(StyleColor::Palette(ColorPalette::LightBlue)));
+
+    let line = |text: &str| match Theme::from_str(text) {
+        Err(NCurseswError::ThemeParseError { line, .. }) => line,
+        _                                                => 0
+    };
+
+    assert_eq!(line("a = { }\n\nb = { fg = \"purple\" }"), 3);
+    assert_eq!(line("a = { attrs = [\"shiny\"] }"), 1);
+    assert_eq!(line("a = { fg = \"red\" \"blue\" }"), 1);
+    assert_eq!(line("a = { }\na = { }"), 2);
+    assert_eq!(line("a = { fg = \"red }"), 1);
+
+    let path = std::env::temp_dir().join(format!("ncursesw-theme-test-{}.theme", std::process::id()));
+
+    fs::write(&path, b"a = { }\nb = { fg = \"\xff\" }\n").unwrap();
+
+    match Theme::from_file(&path) {
+        Err(NCurseswError::ThemeParseError { line, .. }) => assert_eq!(line, 2),
+        result                                           => panic!("unexpected result {:?}", result)
+    }
+
+    fs::remove_file(&path).unwrap();
+}
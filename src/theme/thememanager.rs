@@ -0,0 +1,252 @@
+/*
+    src/theme/thememanager.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{mem, cell::RefCell, collections::HashMap};
+use ascii::AsciiChar;
+use crate::{
+    SCREEN, WINDOW, NCurseswError, AttributesColorPairSet, NCursesColorType,
+    ChtypeChar, ComplexChar, wbkgd, wbkgrnd,
+    normal, extend,
+    theme::{Style, Theme}
+};
+
+/// Manage the current theme of an application.
+///
+/// The styles of the theme are resolved to attributes and color pairs of
+/// the color type when the theme is set, windows registered with a style have
+/// their background set to the style and re-set when the theme is changed.
+///
+/// The color pairs are allocated from a `ColorPairPool` of the theme manager,
+/// the color pairs of a theme that are not used by the new theme are released
+/// when the theme is changed.
+///
+/// ```text
+/// let mut themes = ThemeManager::new(NCursesColorType::Extend, Theme::from_file("dark.theme")?)?;
+///
+/// themes.register_window(stdscr(), "window")?;
+///
+/// // switch to the light theme, stdscr() now has the "window" style of the light theme.
+/// themes.set_theme(Theme::from_file("light.theme")?)?;
+/// ```
+#[derive(Debug)]
+pub struct ThemeManager {
+    screen:      Option<SCREEN>,
+    colortype:   NCursesColorType,
+    theme:       Theme,
+    normal_pool: normal::ColorPairPool,
+    extend_pool: extend::ColorPairPool,
+    resolved:    ResolvedTheme,
+    windows:     Vec<(WINDOW, String)>
+}
+
+// the styles of a theme resolved to attributes and color pairs, the shared
+// color pairs hold the color pairs of the theme allocated in the pool.
+#[derive(Debug)]
+struct ResolvedTheme {
+    styles:  HashMap<String, AttributesColorPairSet>,
+    default: AttributesColorPairSet,
+    _normal: Vec<normal::SharedColorPair>,
+    _extend: Vec<extend::SharedColorPair>
+}
+
+impl ThemeManager {
+    /// Create a theme manager for a color type with an initial theme.
+    pub fn new(colortype: NCursesColorType, theme: Theme) -> result!(Self) {
+        Self::_new(None, colortype, theme)
+    }
+
+    /// Screen function of `new()`.
+    pub fn new_sp(screen: SCREEN, colortype: NCursesColorType, theme: Theme) -> result!(Self) {
+        Self::_new(Some(screen), colortype, theme)
+    }
+
+    fn _new(screen: Option<SCREEN>, colortype: NCursesColorType, theme: Theme) -> result!(Self) {
+        let (normal_pool, extend_pool) = match screen {
+            None         => (normal::ColorPairPool::new(), extend::ColorPairPool::new()),
+            Some(screen) => (normal::ColorPairPool::new_sp(screen), extend::ColorPairPool::new_sp(screen))
+        };
+        let resolved = resolve_theme(screen, colortype, &normal_pool, &extend_pool, &theme)?;
+
+        Ok(Self { screen, colortype, theme, normal_pool, extend_pool, resolved, windows: vec![] })
+    }
+
+    /// The current theme.
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// Set the current theme returning the previous theme, the backgrounds
+    /// of the registered windows are set to their styles of the new theme.
+    ///
+    /// The styles of the new theme are resolved and the backgrounds of the
+    /// registered windows are set before the current theme is replaced, if a
+    /// style can't be resolved or a background can't be set the current theme
+    /// is kept (and the backgrounds already set are restored).
+    pub fn set_theme(&mut self, theme: Theme) -> result!(Theme) {
+        let resolved = resolve_theme(self.screen, self.colortype, &self.normal_pool, &self.extend_pool, &theme)?;
+
+        if let Err(source) = self.windows.iter().try_for_each(|(window, name)| resolved.apply(*window, name)) {
+            for (window, name) in &self.windows {
+                let _ = self.resolved.apply(*window, name);
+            }
+
+            return Err(source);
+        }
+
+        // the color pairs of the previous theme are released when its resolved styles are dropped.
+        self.resolved = resolved;
+
+        Ok(mem::replace(&mut self.theme, theme))
+    }
+
+    /// The attributes and color pair of a named style of the current theme,
+    /// `None` if the theme has no such style.
+    pub fn style(&self, name: &str) -> Option<AttributesColorPairSet> {
+        self.resolved.styles.get(name).copied()
+    }
+
+    /// Register a window with a named style, the background of the window is
+    /// set to the style and re-set each time the theme is changed.
+    ///
+    /// If the current theme has no such style the default style is used.
+    pub fn register_window<S: Into<String>>(&mut self, window: WINDOW, name: S) -> result!(()) {
+        let name = name.into();
+
+        self.resolved.apply(window, &name)?;
+        self.unregister_window(window);
+        self.windows.push((window, name));
+
+        Ok(())
+    }
+
+    /// Unregister a window, the background of the window is left unchanged.
+    /// This should be called before the window is deleted.
+    pub fn unregister_window(&mut self, window: WINDOW) {
+        self.windows.retain(|(registered, _)| *registered != window);
+    }
+}
+
+impl ResolvedTheme {
+    // set the background of a window to a named style.
+    fn apply(&self, window: WINDOW, name: &str) -> result!(()) {
+        match self.styles.get(name).copied().unwrap_or(self.default) {
+            AttributesColorPairSet::Normal(style) => {
+                wbkgd(window, ChtypeChar::new(AsciiChar::Space) | (style.attributes() | style.color_pair()))
+            },
+            AttributesColorPairSet::Extend(style) => {
+                wbkgrnd(window, ComplexChar::from_char(' ', &style.attributes(), &style.color_pair())?)
+            }
+        }
+    }
+}
+
+// resolve the styles of a theme (and the default style) allocating the color
+// pairs from the pool of the color type.
+fn resolve_theme(
+    screen: Option<SCREEN>,
+    colortype: NCursesColorType,
+    normal_pool: &normal::ColorPairPool,
+    extend_pool: &extend::ColorPairPool,
+    theme: &Theme
+) -> result!(ResolvedTheme) {
+    let normal_shared = RefCell::new(vec![]);
+    let extend_shared = RefCell::new(vec![]);
+
+    let resolve = |style: &Style| {
+        style._resolve_with(
+            screen,
+            colortype,
+            |colors| {
+                let shared = normal_pool.alloc(colors)?;
+                let color_pair = shared.color_pair()?;
+
+                normal_shared.borrow_mut().push(shared);
+
+                Ok(color_pair)
+            },
+            |colors| {
+                let shared = extend_pool.alloc(colors)?;
+                let color_pair = shared.color_pair()?;
+
+                extend_shared.borrow_mut().push(shared);
+
+                Ok(color_pair)
+            }
+        )
+    };
+
+    let styles = theme.styles()
+        .iter()
+        .map(|(name, style)| Ok((name.clone(), resolve(style)?)))
+        .collect::<result!(HashMap<String, AttributesColorPairSet>)>()?;
+    let default = resolve(&Style::default())?;
+
+    Ok(ResolvedTheme { styles, default, _normal: normal_shared.into_inner(), _extend: extend_shared.into_inner() })
+}
+
+#[test]
+fn theme_manager_test() {
+    use std::str::FromStr;
+    use crate::{Size, Origin, getbkgd, newwin_sp, start_color_sp, use_default_colors_sp, delwin};
+
+    crate::funcs::with_test_screen(|screen| {
+        start_color_sp(screen).unwrap();
+        use_default_colors_sp(screen).unwrap();
+
+        let dark = Theme::from_str(r#"
+            "error"  = { fg = "red", bg = "black" }
+            "window" = { fg = "white", bg = "black" }
+        "#).unwrap();
+        let light = Theme::from_str(r#"
+            "error"  = { fg = "red", bg = "white" }
+            "window" = { fg = "black", bg = "white" }
+        "#).unwrap();
+
+        let mut themes = ThemeManager::new_sp(screen, NCursesColorType::Normal, dark.clone()).unwrap();
+        let window = newwin_sp(screen, Size { lines: 1, columns: 1 }, Origin { y: 0, x: 0 }).unwrap();
+
+        themes.register_window(window, "window").unwrap();
+
+        // the color pairs of the styles and the default style.
+        assert_eq!(themes.normal_pool.len(), 3);
+
+        let dark_window = getbkgd(window);
+
+        // the color pairs of the dark theme are released.
+        assert_eq!(themes.set_theme(light.clone()).unwrap(), dark);
+        assert_eq!(themes.normal_pool.len(), 3);
+        assert_ne!(getbkgd(window), dark_window);
+
+        let light_window = getbkgd(window);
+
+        // the new theme can't be resolved so the light theme is kept.
+        themes.normal_pool.set_capacity(0);
+
+        assert!(themes.set_theme(dark).is_err());
+        assert_eq!(themes.theme(), &light);
+        assert_eq!(themes.normal_pool.len(), 3);
+        assert_eq!(getbkgd(window), light_window);
+
+        themes.unregister_window(window);
+        delwin(window).unwrap();
+    });
+}
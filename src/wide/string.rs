@@ -126,6 +126,12 @@ impl Into<Vec<wchar_t>> for WideString {
     }
 }
 
+impl Into<String> for WideString {
+    fn into(self) -> String {
+        self.inner.iter().map(|wch| char::from_u32(*wch as u32).unwrap_or(char::REPLACEMENT_CHARACTER)).collect()
+    }
+}
+
 impl From<&str> for WideString {
     fn from(value: &str) -> Self {
         Self { inner: value.chars().map(|chr| u32::from(chr) as wchar_t).collect() }